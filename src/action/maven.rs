@@ -4,6 +4,7 @@ use serde::Deserialize;
 
 use crate::client::{self, Result};
 use crate::client_internals::path::Path;
+use crate::client_internals::JsonResponse;
 use crate::Jenkins;
 
 /// Artifact produced by a build
@@ -42,15 +43,14 @@ impl ShortMavenArtifactRecord {
         &self,
         jenkins_client: &Jenkins,
     ) -> Result<MavenArtifactRecord> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::MavenArtifactRecord { .. } = path {
-            Ok(jenkins_client.get(&path)?.json()?)
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::MavenArtifactRecord { .. } = path.leaf() {
+            Ok(jenkins_client.get(&path)?.json_body()?)
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url.clone(),
                 expected: client::error::ExpectedType::MavenArtifactRecord,
-            }
-            .into())
+            })
         }
     }
 }
@@ -180,3 +180,54 @@ pub struct PipelineApproverAction {
 }
 register_class!("org.jenkinsci.plugins.workflow.support.steps.input.ApproverAction" => PipelineApproverAction);
 impl Action for PipelineApproverAction {}
+
+/// An action describing the change request (pull request, merge request, ...) built by a
+/// branch job
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeRequestAction {
+    /// Identifier of the change request
+    pub id: Option<String>,
+    /// URL of the change request
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+    /// Title of the change request
+    pub title: Option<String>,
+    /// Author of the change request
+    pub author: Option<String>,
+    /// Display name of the author
+    pub author_display_name: Option<String>,
+    /// Email of the author
+    pub author_email: Option<String>,
+}
+register_class!("jenkins.scm.api.actions.ChangeRequestAction" => ChangeRequestAction);
+impl Action for ChangeRequestAction {}
+
+/// An action describing the object (branch, change request, tag, repository, ...) in the SCM
+/// of a job
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMetadataAction {
+    /// Display name of the object
+    pub object_display_name: Option<String>,
+    /// Description of the object
+    pub object_description: Option<String>,
+    /// URL of the object
+    pub object_url: Option<String>,
+}
+register_class!("jenkins.scm.api.metadata.ObjectMetadataAction" => ObjectMetadataAction);
+impl Action for ObjectMetadataAction {}
+
+/// An action describing the contributor of a change request
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributorMetadataAction {
+    /// Identifier of the contributor
+    pub contributor: Option<String>,
+    /// Display name of the contributor
+    pub contributor_display_name: Option<String>,
+    /// Email of the contributor
+    pub contributor_email: Option<String>,
+}
+register_class!("jenkins.scm.api.metadata.ContributorMetadataAction" => ContributorMetadataAction);
+impl Action for ContributorMetadataAction {}
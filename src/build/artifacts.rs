@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Component, Path as FsPath, PathBuf};
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::Artifact;
+use crate::action::maven::MavenArtifactRecord;
+use crate::client::{self, Result};
+use crate::client_internals::path::Path;
+use crate::client_internals::{JsonResponse, NO_PARAMS};
+use crate::Jenkins;
+
+/// Tree query reading the artifacts of a build with their fingerprints
+const ARTIFACTS_TREE: &str =
+    "_class,artifacts[displayPath,fileName,relativePath],fingerprint[fileName,hash]";
+
+/// Fingerprint of a file recorded by a build
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Fingerprint {
+    file_name: String,
+    hash: String,
+}
+
+/// Artifacts archived by a build, with the fingerprints it recorded
+#[derive(Deserialize)]
+struct BuildArtifacts {
+    #[serde(rename = "_class")]
+    class: Option<String>,
+    artifacts: Vec<Artifact>,
+    #[serde(default)]
+    fingerprint: Vec<Fingerprint>,
+}
+
+/// Path to the build at `url`
+fn build_path<'a>(jenkins_client: &Jenkins, url: &'a str) -> Result<Path<'a>> {
+    let path = jenkins_client.url_to_path(url)?;
+    if let Path::Build { .. } = path.leaf() {
+        Ok(path)
+    } else {
+        Err(client::Error::InvalidUrl {
+            url: url.to_string(),
+            expected: client::error::ExpectedType::Build,
+        })
+    }
+}
+
+/// Path to an artifact of the build at `url`
+fn artifact_path<'a>(
+    jenkins_client: &Jenkins,
+    url: &'a str,
+    relative_path: &'a str,
+) -> Result<Path<'a>> {
+    build_path(jenkins_client, url)?
+        .map_leaf(|path| match path {
+            Path::Build {
+                job_name,
+                number,
+                configuration,
+            } => Some(Path::Artifact {
+                job_name,
+                number,
+                configuration,
+                relative_path,
+            }),
+            _ => None,
+        })
+        .ok_or_else(|| client::Error::InvalidUrl {
+            url: url.to_string(),
+            expected: client::error::ExpectedType::Build,
+        })
+}
+
+/// Stream the artifact of the build at `url` to `writer`
+pub(crate) fn download_artifact<W: Write + ?Sized>(
+    jenkins_client: &Jenkins,
+    url: &str,
+    artifact: &Artifact,
+    writer: &mut W,
+) -> Result<u64> {
+    let path = artifact_path(jenkins_client, url, &artifact.relative_path)?;
+    Ok(jenkins_client.get_raw(&path, NO_PARAMS)?.copy_to(writer)?)
+}
+
+/// Download all artifacts of the build at `url` in `dir`, keeping their relative paths
+pub(crate) fn download_all_artifacts(
+    jenkins_client: &Jenkins,
+    url: &str,
+    dir: &FsPath,
+) -> Result<Vec<PathBuf>> {
+    let path = build_path(jenkins_client, url)?;
+    let build: BuildArtifacts = jenkins_client
+        .get_with_params(&path, [("tree", ARTIFACTS_TREE)])?
+        .json_body()?;
+    // The relative paths are sent by the server and must stay inside `dir`
+    if let Some(artifact) = build.artifacts.iter().find(|artifact| {
+        FsPath::new(&artifact.relative_path)
+            .components()
+            .any(|component| match component {
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => true,
+                Component::CurDir | Component::Normal(_) => false,
+            })
+    }) {
+        return Err(client::Error::InvalidArtifactPath {
+            path: artifact.relative_path.clone(),
+        });
+    }
+    let checksums = known_checksums(jenkins_client, path, &build)?;
+
+    let mut files = Vec::with_capacity(build.artifacts.len());
+    for artifact in &build.artifacts {
+        let target = dir.join(&artifact.relative_path);
+        let expected = checksums.get(&artifact.relative_path).map(String::as_str);
+        let path = artifact_path(jenkins_client, url, &artifact.relative_path)?;
+        download_to_file(jenkins_client, &path, &target, expected)?;
+        files.push(target);
+    }
+    Ok(files)
+}
+
+/// MD5 checksums of the artifacts of a build by relative path, from its fingerprints and its
+/// maven artifacts
+fn known_checksums(
+    jenkins_client: &Jenkins,
+    path: Path,
+    build: &BuildArtifacts,
+) -> Result<HashMap<String, String>> {
+    let mut recorded: Vec<(String, String)> = build
+        .fingerprint
+        .iter()
+        .map(|fingerprint| (fingerprint.file_name.clone(), fingerprint.hash.clone()))
+        .collect();
+    if build.class.as_deref() == Some("hudson.maven.MavenBuild") {
+        let record_path = path.map_leaf(|path| match path {
+            Path::Build {
+                job_name,
+                number,
+                configuration,
+            } => Some(Path::MavenArtifactRecord {
+                job_name,
+                number,
+                configuration,
+            }),
+            _ => None,
+        });
+        if let Some(record_path) = record_path {
+            let record: MavenArtifactRecord = jenkins_client.get(&record_path)?.json_body()?;
+            recorded.extend(
+                std::iter::once(record.main_artifact)
+                    .chain(std::iter::once(record.pom_artifact))
+                    .chain(record.attached_artifacts)
+                    .map(|artifact| (artifact.file_name, artifact.md5sum)),
+            );
+        }
+    }
+
+    let mut checksums = HashMap::new();
+    for (file_name, hash) in recorded {
+        if let Some(artifact) = build
+            .artifacts
+            .iter()
+            .find(|artifact| artifact.relative_path == file_name)
+        {
+            let _ = checksums.insert(artifact.relative_path.clone(), hash);
+            continue;
+        }
+        // A file name shared by several artifacts doesn't tell which one was recorded
+        let mut matching = build
+            .artifacts
+            .iter()
+            .filter(|artifact| artifact.file_name == file_name);
+        if let (Some(artifact), None) = (matching.next(), matching.next()) {
+            let _ = checksums.insert(artifact.relative_path.clone(), hash);
+        }
+    }
+    Ok(checksums)
+}
+
+/// Download an artifact to `target`, checking it against its `expected` MD5 checksum if it is
+/// known
+///
+/// A file already present is skipped if it matches its checksum, or else resumed. Without a
+/// checksum to verify it, it is downloaded again.
+fn download_to_file(
+    jenkins_client: &Jenkins,
+    path: &Path,
+    target: &FsPath,
+    expected: Option<&str>,
+) -> Result<()> {
+    let io_error = |source| client::Error::Io {
+        path: target.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+
+    let expected = match expected {
+        Some(expected) => expected,
+        None => return download_whole(jenkins_client, path, target),
+    };
+    let resumed = match fs::metadata(target) {
+        Ok(metadata) => {
+            if file_checksum(target)? == expected {
+                return Ok(());
+            }
+            resume_download(jenkins_client, path, target, metadata.len())?;
+            true
+        }
+        Err(_) => {
+            download_whole(jenkins_client, path, target)?;
+            false
+        }
+    };
+
+    let mut actual = file_checksum(target)?;
+    if actual != expected && resumed {
+        // The partial file may not have been a prefix of the artifact
+        download_whole(jenkins_client, path, target)?;
+        actual = file_checksum(target)?;
+    }
+    if actual != expected {
+        return Err(client::Error::ArtifactChecksumMismatch {
+            path: target.to_path_buf(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Download a whole artifact to `target`, replacing its content
+fn download_whole(jenkins_client: &Jenkins, path: &Path, target: &FsPath) -> Result<()> {
+    let mut file = File::create(target).map_err(|source| client::Error::Io {
+        path: target.to_path_buf(),
+        source,
+    })?;
+    let _ = jenkins_client
+        .get_raw(path, NO_PARAMS)?
+        .copy_to(&mut file)?;
+    Ok(())
+}
+
+/// Download the end of an artifact after the `length` bytes already in `target`, which must
+/// be checked against its checksum afterwards
+fn resume_download(
+    jenkins_client: &Jenkins,
+    path: &Path,
+    target: &FsPath,
+    length: u64,
+) -> Result<()> {
+    let io_error = |source| client::Error::Io {
+        path: target.to_path_buf(),
+        source,
+    };
+    match jenkins_client.get_raw_from(path, length) {
+        Ok(mut response) => {
+            let mut file = if response.status() == StatusCode::PARTIAL_CONTENT {
+                OpenOptions::new().append(true).open(target)
+            } else {
+                File::create(target)
+            }
+            .map_err(io_error)?;
+            let _ = response.copy_to(&mut file)?;
+            Ok(())
+        }
+        // The file may already be complete, or be longer than the artifact
+        Err(client::Error::ClientError { status: 416, .. }) => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+/// MD5 checksum of a file, in lowercase hexadecimal like Jenkins fingerprints
+fn file_checksum(target: &FsPath) -> Result<String> {
+    let io_error = |source| client::Error::Io {
+        path: target.to_path_buf(),
+        source,
+    };
+    let mut file = File::open(target).map_err(io_error)?;
+    let mut context = md5::Context::new();
+    let _ = io::copy(&mut file, &mut context).map_err(io_error)?;
+    Ok(format!("{:x}", context.compute()))
+}
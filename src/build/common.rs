@@ -1,12 +1,18 @@
+use std::fmt;
 use std::marker::PhantomData;
+use std::time::{Duration, Instant};
 
 use serde::{self, Deserialize, Serialize};
 
 use crate::helpers::Class;
 
+use super::{artifacts, ConsoleStream, TestReport};
 use crate::action::CommonAction;
-use crate::client::{self, Result};
-use crate::client_internals::path::Path;
+use crate::client::{self, AdvancedQuery, Result};
+use crate::client_internals::path::{BuildAction, Path};
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
+use crate::client_internals::{InternalAdvancedQueryParams, JsonResponse};
 use crate::job::{CommonJob, Job};
 use crate::Jenkins;
 
@@ -39,21 +45,119 @@ where
     T: Build,
     for<'de> T: Deserialize<'de>,
 {
+    /// Wait for the build to finish, checking its status every `poll_interval`, and return
+    /// its result
+    ///
+    /// See [`Build::wait_for_completion`](trait.Build.html#method.wait_for_completion)
+    pub fn wait_for_completion(
+        &self,
+        jenkins_client: &Jenkins,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<BuildStatus> {
+        wait_for_completion(jenkins_client, &self.url, poll_interval, timeout)
+    }
+
     /// Get the full details of a `Build` matching the `ShortBuild`
     pub fn get_full_build(&self, jenkins_client: &Jenkins) -> Result<T> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::Build { .. } = path {
-            return Ok(jenkins_client.get(&path)?.json()?);
-        } else if let Path::InFolder { path: sub_path, .. } = &path {
-            if let Path::Build { .. } = sub_path.as_ref() {
-                return Ok(jenkins_client.get(&path)?.json()?);
-            }
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::Build { .. } = path.leaf() {
+            return jenkins_client.get(&path)?.json_body();
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url.clone(),
+            expected: client::error::ExpectedType::Build,
+        })
+    }
+
+    /// Get the full details of a `Build` matching the `ShortBuild` with an asynchronous client
+    #[cfg(feature = "async")]
+    pub async fn get_full_build_async(&self, jenkins_client: &crate::AsyncJenkins) -> Result<T> {
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::Build { .. } = path.leaf() {
+            return jenkins_client.get(&path).await?.json_body().await;
         }
         Err(client::Error::InvalidUrl {
             url: self.url.clone(),
             expected: client::error::ExpectedType::Build,
+        })
+    }
+}
+
+/// Poll the build at `url` until it is finished
+fn wait_for_completion(
+    jenkins_client: &Jenkins,
+    url: &str,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<BuildStatus> {
+    #[derive(Deserialize)]
+    struct BuildState {
+        building: bool,
+        result: Option<BuildStatus>,
+    }
+
+    let path = jenkins_client.url_to_path(url)?;
+    if !matches!(path.leaf(), Path::Build { .. }) {
+        return Err(client::Error::InvalidUrl {
+            url: url.to_string(),
+            expected: client::error::ExpectedType::Build,
+        });
+    }
+    let start = Instant::now();
+    loop {
+        let state: BuildState = jenkins_client
+            .get_with_params(&path, [("tree", "building,result")])?
+            .json_body()?;
+        match state {
+            BuildState {
+                building: false,
+                result: Some(result),
+            } => return Ok(result),
+            _ => {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Err(client::Error::Timeout {
+                        url: url.to_string(),
+                        timeout,
+                    });
+                }
+                std::thread::sleep(poll_interval.min(timeout - elapsed));
+            }
         }
-        .into())
+    }
+}
+
+/// Post `action` to the build at `url`
+fn post_build_action(
+    jenkins_client: &Jenkins,
+    url: &str,
+    action: BuildAction,
+    qps: &[(&str, &str)],
+) -> Result<()> {
+    let path = jenkins_client
+        .url_to_path(url)?
+        .map_leaf(|path| match path {
+            Path::Build {
+                job_name,
+                number,
+                configuration,
+            } => Some(Path::BuildAction {
+                job_name,
+                number,
+                configuration,
+                action,
+            }),
+            _ => None,
+        });
+    if let Some(path) = path {
+        let _ = jenkins_client.post_with_body(&path, "", qps)?;
+        Ok(())
+    } else {
+        Err(client::Error::InvalidUrl {
+            url: url.to_string(),
+            expected: client::error::ExpectedType::Build,
+        })
     }
 }
 
@@ -105,9 +209,9 @@ pub enum BuildNumber {
     /// Unknown alias
     UnknwonAlias(String),
 }
-impl ToString for BuildNumber {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for BuildNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&match self {
             BuildNumber::LastBuild => "lastBuild".to_string(),
             BuildNumber::LastSuccessfulBuild => "lastSuccessfulBuild".to_string(),
             BuildNumber::LastStableBuild => "lastStableBuild".to_string(),
@@ -116,7 +220,7 @@ impl ToString for BuildNumber {
             BuildNumber::LastUnsuccessfulBuild => "lastUnsuccessfulBuild".to_string(),
             BuildNumber::Number(n) => n.to_string(),
             BuildNumber::UnknwonAlias(s) => s.to_string(),
-        }
+        })
     }
 }
 impl<'a> From<&'a str> for BuildNumber {
@@ -176,92 +280,248 @@ pub trait Build {
     where
         for<'de> Self::ParentJob: Deserialize<'de>,
     {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Build {
-            job_name,
-            configuration,
-            ..
-        } = path
-        {
-            return Ok(jenkins_client
-                .get(&Path::Job {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Build {
+                    job_name,
+                    configuration,
+                    ..
+                } => Some(Path::Job {
                     name: job_name,
                     configuration,
-                })?
-                .json()?);
-        } else if let Path::InFolder {
-            path: sub_path,
-            folder_name,
-        } = &path
-        {
-            if let Path::Build {
-                job_name,
-                configuration,
-                ..
-            } = sub_path.as_ref()
-            {
-                return Ok(jenkins_client
-                    .get(&Path::InFolder {
-                        folder_name: folder_name.clone(),
-                        path: Box::new(Path::Job {
-                            name: job_name.clone(),
-                            configuration: configuration.clone(),
-                        }),
-                    })?
-                    .json()?);
-            }
+                }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            return jenkins_client.get(&path)?.json_body();
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url().to_string(),
+            expected: client::error::ExpectedType::Build,
+        })
+    }
+
+    /// Wait for the build to finish, checking its status every `poll_interval`, and return
+    /// its result
+    ///
+    /// # Errors
+    /// If the build hasn't finished after `timeout`, this method will return an
+    /// [`Error::Timeout`](../enum.Error.html#variant.Timeout)
+    fn wait_for_completion(
+        &self,
+        jenkins_client: &Jenkins,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<BuildStatus> {
+        wait_for_completion(jenkins_client, self.url(), poll_interval, timeout)
+    }
+
+    /// Get the test report of this `Build`
+    fn get_test_report(&self, jenkins_client: &Jenkins) -> Result<TestReport> {
+        self.get_test_report_with(jenkins_client, None)
+    }
+
+    /// Get the test report of this `Build`, specifying the depth or tree parameters
+    ///
+    /// With a `tree` query, large reports can be read in slices by using
+    /// [`TreeBuilder::with_range`](../client/struct.TreeBuilder.html#method.with_range) on
+    /// `suites` or `cases`.
+    fn get_test_report_with<Q>(&self, jenkins_client: &Jenkins, parameters: Q) -> Result<TestReport>
+    where
+        Q: Into<Option<AdvancedQuery>>,
+    {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Build {
+                    job_name,
+                    number,
+                    configuration,
+                } => Some(Path::TestReport {
+                    job_name,
+                    number,
+                    configuration,
+                }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            return jenkins_client
+                .get_with_params(
+                    &path,
+                    parameters.into().map(InternalAdvancedQueryParams::from),
+                )?
+                .json_body();
         }
+
         Err(client::Error::InvalidUrl {
             url: self.url().to_string(),
             expected: client::error::ExpectedType::Build,
+        })
+    }
+
+    /// Stream an `Artifact` of this `Build` to `writer`, returning the number of bytes written
+    fn download_artifact<W: std::io::Write + ?Sized>(
+        &self,
+        jenkins_client: &Jenkins,
+        artifact: &Artifact,
+        writer: &mut W,
+    ) -> Result<u64> {
+        artifacts::download_artifact(jenkins_client, self.url(), artifact, writer)
+    }
+
+    /// Download all artifacts of this `Build` in `dir`, keeping their relative paths, and
+    /// return the paths of the files
+    ///
+    /// When the build recorded fingerprints, or for maven builds, downloaded files are checked
+    /// against their MD5 checksum. Files already present in `dir` are skipped if they match
+    /// their checksum, or else the end of the artifact is downloaded. Files without a known
+    /// checksum are always downloaded again.
+    ///
+    /// # Errors
+    /// If a file doesn't match its checksum, this method will return an
+    /// [`Error::ArtifactChecksumMismatch`](../enum.Error.html#variant.ArtifactChecksumMismatch),
+    /// and if the relative path of an artifact points outside of `dir`, an
+    /// [`Error::InvalidArtifactPath`](../enum.Error.html#variant.InvalidArtifactPath)
+    fn download_all_artifacts<P: AsRef<std::path::Path>>(
+        &self,
+        jenkins_client: &Jenkins,
+        dir: P,
+    ) -> Result<Vec<std::path::PathBuf>> {
+        artifacts::download_all_artifacts(jenkins_client, self.url(), dir.as_ref())
+    }
+
+    /// Abort a running `Build`, like the stop button of Jenkins
+    fn stop(&self, jenkins_client: &Jenkins) -> Result<()> {
+        post_build_action(jenkins_client, self.url(), BuildAction::Stop, &[])
+    }
+
+    /// Forcibly terminate a pipeline `Build` that didn't stop after
+    /// [`stop`](#method.stop)
+    fn term(&self, jenkins_client: &Jenkins) -> Result<()> {
+        post_build_action(jenkins_client, self.url(), BuildAction::Term, &[])
+    }
+
+    /// Hard kill a pipeline `Build` that didn't stop after [`term`](#method.term)
+    fn kill(&self, jenkins_client: &Jenkins) -> Result<()> {
+        post_build_action(jenkins_client, self.url(), BuildAction::Kill, &[])
+    }
+
+    /// Delete this `Build`
+    fn delete(&self, jenkins_client: &Jenkins) -> Result<()> {
+        post_build_action(jenkins_client, self.url(), BuildAction::Delete, &[])
+    }
+
+    /// Toggle keeping this `Build` forever. It may need to be refreshed as it may have been
+    /// updated
+    fn toggle_keep_log(&self, jenkins_client: &Jenkins) -> Result<()> {
+        post_build_action(jenkins_client, self.url(), BuildAction::ToggleKeepLog, &[])
+    }
+
+    /// Set the description of this `Build`. It may need to be refreshed as it may have been
+    /// updated
+    fn set_description(&self, jenkins_client: &Jenkins, description: &str) -> Result<()> {
+        post_build_action(
+            jenkins_client,
+            self.url(),
+            BuildAction::SubmitDescription,
+            &[("description", description)],
+        )
+    }
+
+    /// Set the display name of this `Build`, an empty `display_name` resetting it to the
+    /// default one. It may need to be refreshed as it may have been updated
+    fn set_display_name(&self, jenkins_client: &Jenkins, display_name: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        struct BuildDescription {
+            description: Option<String>,
+        }
+
+        // Jenkins updates both the display name and the description of the build together,
+        // so the current description is sent back unchanged
+        let path = jenkins_client.url_to_path(self.url())?;
+        if !matches!(path.leaf(), Path::Build { .. }) {
+            return Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Build,
+            });
+        }
+        let current: BuildDescription = jenkins_client
+            .get_with_params(&path, [("tree", "description")])?
+            .json_body()?;
+        let config = serde_json::json!({
+            "displayName": display_name,
+            "description": current.description.unwrap_or_default(),
+        });
+        post_build_action(
+            jenkins_client,
+            self.url(),
+            BuildAction::ConfigSubmit,
+            &[("json", &config.to_string())],
+        )
+    }
+
+    /// Stream the console output of a `Build`, reading it progressively while the build is
+    /// running
+    fn stream_console<'a>(&self, jenkins_client: &'a Jenkins) -> Result<ConsoleStream<'a>> {
+        self.stream_console_from(jenkins_client, 0)
+    }
+
+    /// Stream the console output of a `Build` from the byte `offset`, like the
+    /// [`ConsoleStream::offset`](struct.ConsoleStream.html#method.offset) of a previous stream
+    fn stream_console_from<'a>(
+        &self,
+        jenkins_client: &'a Jenkins,
+        offset: u64,
+    ) -> Result<ConsoleStream<'a>> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Build {
+                    job_name,
+                    number,
+                    configuration,
+                } => Some(Path::ProgressiveText {
+                    job_name,
+                    number,
+                    configuration,
+                }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            return Ok(ConsoleStream::new(jenkins_client, &path, offset));
         }
-        .into())
+
+        Err(client::Error::InvalidUrl {
+            url: self.url().to_string(),
+            expected: client::error::ExpectedType::Build,
+        })
     }
 
     /// Get the console output from a `Build`
     fn get_console(&self, jenkins_client: &Jenkins) -> Result<String> {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Build {
-            job_name,
-            number,
-            configuration,
-        } = path
-        {
-            return Ok(jenkins_client
-                .get(&Path::ConsoleText {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Build {
                     job_name,
                     number,
                     configuration,
-                    folder_name: None,
-                })?
-                .text()?);
-        } else if let Path::InFolder {
-            path: sub_path,
-            folder_name,
-        } = &path
-        {
-            if let Path::Build {
-                job_name,
-                number,
-                configuration,
-            } = sub_path.as_ref()
-            {
-                return Ok(jenkins_client
-                    .get(&Path::ConsoleText {
-                        job_name: job_name.clone(),
-                        number: number.clone(),
-                        configuration: configuration.clone(),
-                        folder_name: Some(folder_name.clone()),
-                    })?
-                    .text()?);
-            }
+                } => Some(Path::ConsoleText {
+                    job_name,
+                    number,
+                    configuration,
+                }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            return Ok(jenkins_client.get(&path)?.text()?);
         }
 
         Err(client::Error::InvalidUrl {
             url: self.url().to_string(),
             expected: client::error::ExpectedType::Build,
-        }
-        .into())
+        })
     }
 }
 
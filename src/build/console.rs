@@ -0,0 +1,111 @@
+use std::io::{self, Read};
+use std::time::Duration;
+
+use crate::client::Result;
+use crate::client_internals::path::Path;
+use crate::Jenkins;
+
+/// Interval between two requests while the build is running and has no new output
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stream of the console output of a `Build`, read progressively while the build is running
+///
+/// It is an `Iterator` over the new chunks of output, and implements `Read`. It ends when the
+/// build is finished and all its output has been read.
+#[derive(Debug)]
+pub struct ConsoleStream<'a> {
+    jenkins_client: &'a Jenkins,
+    path: String,
+    offset: u64,
+    finished: bool,
+    poll_interval: Duration,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<'a> ConsoleStream<'a> {
+    pub(crate) fn new(jenkins_client: &'a Jenkins, path: &Path, offset: u64) -> Self {
+        ConsoleStream {
+            jenkins_client,
+            path: path.to_string(),
+            offset,
+            finished: false,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            buffer: vec![],
+            position: 0,
+        }
+    }
+
+    /// Wait `poll_interval` before asking again for new output of a running build
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Offset in bytes of the output read so far, to resume reading from it later
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Get the output from the current offset, returning the new output and if the build is
+    /// still running
+    fn read_chunk(&mut self) -> Result<(Vec<u8>, bool)> {
+        let offset = self.offset.to_string();
+        let response = self.jenkins_client.get_raw(
+            &Path::Raw { path: &self.path },
+            [("start", offset.as_str())],
+        )?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let more_data = header("X-More-Data").as_deref() == Some("true");
+        let text_size = header("X-Text-Size").and_then(|value| value.parse().ok());
+        let chunk = response.bytes()?.to_vec();
+        self.offset = text_size.unwrap_or(self.offset + chunk.len() as u64);
+        Ok((chunk, more_data))
+    }
+}
+
+impl<'a> Iterator for ConsoleStream<'a> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            match self.read_chunk() {
+                Ok((chunk, more_data)) => {
+                    self.finished = !more_data;
+                    if !chunk.is_empty() {
+                        return Some(Ok(chunk));
+                    }
+                    if more_data {
+                        std::thread::sleep(self.poll_interval);
+                    }
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Read for ConsoleStream<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.buffer.len() {
+            match self.next() {
+                Some(Ok(chunk)) => {
+                    self.buffer = chunk;
+                    self.position = 0;
+                }
+                Some(Err(error)) => return Err(io::Error::other(error)),
+                None => return Ok(0),
+            }
+        }
+        let read = (&self.buffer[self.position..]).read(buf)?;
+        self.position += read;
+        Ok(read)
+    }
+}
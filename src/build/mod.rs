@@ -1,13 +1,19 @@
 //! Jenkins Builds
 
 use crate::client::Result;
-use crate::client_internals::path::{Name, Path};
+use crate::client_internals::path::Path;
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
+use crate::client_internals::JsonResponse;
 use crate::job::JobName;
 use crate::Jenkins;
 
 #[macro_use]
 mod common;
 pub use self::common::{Artifact, Build, BuildNumber, BuildStatus, CommonBuild, ShortBuild};
+mod artifacts;
+mod console;
+pub use self::console::ConsoleStream;
 mod flow;
 pub use self::flow::BuildFlowRun;
 mod freestyle;
@@ -20,20 +26,78 @@ mod maven;
 pub use self::maven::{MavenBuild, MavenModuleSetBuild};
 mod multijob;
 pub use self::multijob::MultiJobBuild;
+mod stages;
+pub use self::stages::{NodeLog, PipelineRun, Stage, StageError, StageStatus, StageStep};
+mod test_report;
+pub use self::test_report::{
+    ChildTestReport, TestCase, TestComparison, TestReport, TestStatus, TestSuite,
+};
 
 impl Jenkins {
-    /// Get a build from a `job_name` and `build_number`
+    /// Get a build from a `job_name` and `build_number`, the job name can be a full name like
+    /// `folder/job`
     pub fn get_build<'a, J, B>(&self, job_name: J, build_number: B) -> Result<CommonBuild>
     where
         J: Into<JobName<'a>>,
         B: Into<BuildNumber>,
     {
-        Ok(self
-            .get(&Path::Build {
-                job_name: Name::Name(job_name.into().0),
+        self.get(&Path::for_job(job_name.into().0, false, |job_name| {
+            Path::Build {
+                job_name,
                 number: build_number.into(),
                 configuration: None,
-            })?
-            .json()?)
+            }
+        }))?
+        .json_body()
+    }
+
+    /// Compare the test results of two builds of a job, like `lastSuccessfulBuild` and
+    /// `lastBuild`, the job name can be a full name like `folder/job`
+    pub fn compare_test_reports<'a, J, B, C>(
+        &self,
+        job_name: J,
+        base: B,
+        compared: C,
+    ) -> Result<TestComparison>
+    where
+        J: Into<JobName<'a>>,
+        B: Into<BuildNumber>,
+        C: Into<BuildNumber>,
+    {
+        let job_name = job_name.into().0;
+        let get_test_report = |number: BuildNumber| -> Result<TestReport> {
+            self.get(&Path::for_job(job_name, false, |job_name| {
+                Path::TestReport {
+                    job_name,
+                    number,
+                    configuration: None,
+                }
+            }))?
+            .json_body()
+        };
+        let base = get_test_report(base.into())?;
+        Ok(get_test_report(compared.into())?.compare_with(&base))
+    }
+}
+
+#[cfg(feature = "async")]
+impl crate::AsyncJenkins {
+    /// Get a build from a `job_name` and `build_number`, the job name can be a full name like
+    /// `folder/job`
+    pub async fn get_build<'a, J, B>(&self, job_name: J, build_number: B) -> Result<CommonBuild>
+    where
+        J: Into<JobName<'a>>,
+        B: Into<BuildNumber>,
+    {
+        self.get(&Path::for_job(job_name.into().0, false, |job_name| {
+            Path::Build {
+                job_name,
+                number: build_number.into(),
+                configuration: None,
+            }
+        }))
+        .await?
+        .json_body()
+        .await
     }
 }
@@ -2,10 +2,13 @@ use serde::Deserialize;
 
 use crate::helpers::Class;
 
-use super::{Artifact, Build, BuildStatus, ShortBuild};
+use super::{Artifact, Build, BuildNumber, BuildStatus, NodeLog, PipelineRun, ShortBuild, Stage};
 use crate::action::CommonAction;
 use crate::changeset;
+use crate::client::{self, Result};
+use crate::client_internals::{JsonResponse, Name, Path, NO_PARAMS};
 use crate::job::WorkflowJob;
+use crate::Jenkins;
 
 build_with_common_fields_and_impl!(
     /// A `Build` from a WorkflowJob
@@ -20,4 +23,54 @@ build_with_common_fields_and_impl!(
 );
 register_class!("org.jenkinsci.plugins.workflow.job.WorkflowRun" => WorkflowRun);
 
-impl WorkflowRun {}
+impl WorkflowRun {
+    /// Get the stages of this run, as shown by the pipeline stage view
+    pub fn describe(&self, jenkins_client: &Jenkins) -> Result<PipelineRun> {
+        let path = self.run_path(jenkins_client, |job_name, number| {
+            Path::WorkflowRunDescribe { job_name, number }
+        })?;
+        jenkins_client.get_raw(&path, NO_PARAMS)?.json_body()
+    }
+
+    /// Get the stage with the node ID `stage_id` with its steps
+    pub fn get_stage(&self, jenkins_client: &Jenkins, stage_id: &str) -> Result<Stage> {
+        let path = self.run_path(jenkins_client, |job_name, number| {
+            Path::WorkflowNodeDescribe {
+                job_name,
+                number,
+                node_id: Name::Name(stage_id),
+            }
+        })?;
+        jenkins_client.get_raw(&path, NO_PARAMS)?.json_body()
+    }
+
+    /// Get an excerpt of the log of the stage or step with the node ID `node_id`
+    pub fn get_node_log(&self, jenkins_client: &Jenkins, node_id: &str) -> Result<NodeLog> {
+        let path = self.run_path(jenkins_client, |job_name, number| Path::WorkflowNodeLog {
+            job_name,
+            number,
+            node_id: Name::Name(node_id),
+        })?;
+        jenkins_client.get_raw(&path, NO_PARAMS)?.json_body()
+    }
+
+    fn run_path<'a, F>(&'a self, jenkins_client: &Jenkins, f: F) -> Result<Path<'a>>
+    where
+        F: FnOnce(Name<'a>, BuildNumber) -> Path<'a>,
+    {
+        jenkins_client
+            .url_to_path(&self.url)?
+            .map_leaf(|path| match path {
+                Path::Build {
+                    job_name,
+                    number,
+                    configuration: None,
+                } => Some(f(job_name, number)),
+                _ => None,
+            })
+            .ok_or_else(|| client::Error::InvalidUrl {
+                url: self.url.clone(),
+                expected: client::error::ExpectedType::Build,
+            })
+    }
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+/// Status of a pipeline run, stage or step, as reported by the pipeline stage view
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StageStatus {
+    /// Successful
+    Success,
+    /// Unstable
+    Unstable,
+    /// Failed
+    Failed,
+    /// Aborted
+    Aborted,
+    /// Not executed, like a stage skipped after a failure
+    NotExecuted,
+    /// Running
+    InProgress,
+    /// Waiting for an input
+    PausedPendingInput,
+    /// Status not known by this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Error of a failed stage or step
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StageError {
+    /// Message of the error
+    pub message: String,
+    /// Class of the error
+    #[serde(rename = "type")]
+    pub error_type: Option<String>,
+}
+
+/// A step of a pipeline `Stage`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StageStep {
+    /// ID of the node of the step
+    pub id: String,
+    /// Name of the step, like `Shell Script`
+    pub name: String,
+    /// Agent running the step
+    pub exec_node: Option<String>,
+    /// Status of the step
+    pub status: StageStatus,
+    /// Description of the parameters of the step, like the shell script run
+    pub parameter_description: Option<String>,
+    /// Timestamp of the step start, in milliseconds
+    pub start_time_millis: u64,
+    /// Duration in milliseconds
+    pub duration_millis: u64,
+    /// Time spent waiting for an input, in milliseconds
+    pub pause_duration_millis: u64,
+    /// Error of a failed step
+    pub error: Option<StageError>,
+}
+
+/// A stage of a pipeline run
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Stage {
+    /// ID of the node of the stage
+    pub id: String,
+    /// Name of the stage
+    pub name: String,
+    /// Agent running the stage
+    pub exec_node: Option<String>,
+    /// Status of the stage
+    pub status: StageStatus,
+    /// Timestamp of the stage start, in milliseconds
+    pub start_time_millis: u64,
+    /// Duration in milliseconds
+    pub duration_millis: u64,
+    /// Time spent waiting for an input, in milliseconds
+    pub pause_duration_millis: u64,
+    /// Error of a failed stage
+    pub error: Option<StageError>,
+    /// Steps of the stage, only read with
+    /// [`WorkflowRun::get_stage`](struct.WorkflowRun.html#method.get_stage)
+    #[serde(default, rename = "stageFlowNodes")]
+    pub steps: Vec<StageStep>,
+}
+
+/// Description of a pipeline run with its stages
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRun {
+    /// ID of the run, which is its build number
+    pub id: String,
+    /// Name of the run
+    pub name: String,
+    /// Status of the run
+    pub status: StageStatus,
+    /// Timestamp of the run start, in milliseconds
+    pub start_time_millis: u64,
+    /// Timestamp of the run end, in milliseconds
+    pub end_time_millis: u64,
+    /// Duration in milliseconds
+    pub duration_millis: u64,
+    /// Time spent in the queue, in milliseconds
+    pub queue_duration_millis: u64,
+    /// Time spent waiting for an input, in milliseconds
+    pub pause_duration_millis: u64,
+    /// Stages of the run
+    #[serde(default)]
+    pub stages: Vec<Stage>,
+}
+
+impl PipelineRun {
+    /// The first stage that failed
+    pub fn failed_stage(&self) -> Option<&Stage> {
+        self.stages
+            .iter()
+            .find(|stage| stage.status == StageStatus::Failed)
+    }
+}
+
+/// Log of a stage or a step
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeLog {
+    /// ID of the node
+    pub node_id: String,
+    /// Status of the node
+    pub node_status: StageStatus,
+    /// Length of the full log
+    pub length: u64,
+    /// Is the log longer than `text`
+    pub has_more: bool,
+    /// Excerpt of the log, as HTML
+    #[serde(default)]
+    pub text: String,
+    /// Path to the full log
+    pub console_url: Option<String>,
+}
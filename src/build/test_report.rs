@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::ShortBuild;
+
+/// Status of a test case
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TestStatus {
+    /// Test passed
+    Passed,
+    /// Test skipped
+    Skipped,
+    /// Test failed, and was already failing in the previous build
+    Failed,
+    /// Test passed, and was failing in the previous build
+    Fixed,
+    /// Test failed, and was passing in the previous build
+    Regression,
+}
+
+impl TestStatus {
+    /// Is the test failing
+    pub fn is_failure(self) -> bool {
+        matches!(self, TestStatus::Failed | TestStatus::Regression)
+    }
+}
+
+/// A test case of a `TestSuite`
+///
+/// Fields not read by a `tree` query are left to their default value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TestCase {
+    /// Name of the class of the test
+    pub class_name: String,
+    /// Name of the test
+    pub name: String,
+    /// Duration in seconds
+    pub duration: f64,
+    /// Status of the test
+    pub status: Option<TestStatus>,
+    /// Number of builds the test has been failing for
+    pub age: u32,
+    /// Number of the first build where the test failed
+    pub failed_since: u32,
+    /// Is the test skipped
+    pub skipped: bool,
+    /// Message explaining why the test was skipped
+    pub skipped_message: Option<String>,
+    /// Error message of a failing test
+    pub error_details: Option<String>,
+    /// Stack trace of a failing test
+    pub error_stack_trace: Option<String>,
+    /// Standard output of the test
+    pub stdout: Option<String>,
+    /// Standard error of the test
+    pub stderr: Option<String>,
+}
+
+/// A test suite of a `TestReport`
+///
+/// Fields not read by a `tree` query are left to their default value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TestSuite {
+    /// Name of the suite
+    pub name: String,
+    /// Identifier of the suite
+    pub id: Option<String>,
+    /// Duration in seconds
+    pub duration: f64,
+    /// Timestamp of the suite as found in the report
+    pub timestamp: Option<String>,
+    /// Standard output of the suite
+    pub stdout: Option<String>,
+    /// Standard error of the suite
+    pub stderr: Option<String>,
+    /// Test cases of the suite
+    pub cases: Vec<TestCase>,
+}
+
+/// Test report of a build aggregated in the report of its parent build
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChildTestReport {
+    /// Build of the report
+    pub child: Option<ShortBuild>,
+    /// Test report of the build
+    pub result: TestReport,
+}
+
+/// Test report of a `Build`
+///
+/// For builds aggregating the reports of other builds, like matrix builds or maven module sets,
+/// the reports of those builds are in `child_reports`. Fields not read by a `tree` query are
+/// left to their default value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TestReport {
+    /// _class provided by Jenkins
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Duration in seconds
+    pub duration: f64,
+    /// Number of tests failed
+    pub fail_count: u32,
+    /// Number of tests skipped
+    pub skip_count: u32,
+    /// Number of tests passed, for reports that aren't aggregated
+    pub pass_count: Option<u32>,
+    /// Number of tests, for aggregated reports
+    pub total_count: Option<u32>,
+    /// Test suites of the report
+    pub suites: Vec<TestSuite>,
+    /// Reports aggregated in this report
+    pub child_reports: Vec<ChildTestReport>,
+}
+
+impl TestReport {
+    /// Test suites of the report, including the suites of the aggregated reports
+    pub fn all_suites(&self) -> Vec<&TestSuite> {
+        self.suites
+            .iter()
+            .chain(
+                self.child_reports
+                    .iter()
+                    .flat_map(|child| child.result.all_suites()),
+            )
+            .collect()
+    }
+
+    /// Test cases of the report, including the cases of the aggregated reports
+    pub fn all_cases(&self) -> Vec<&TestCase> {
+        self.all_suites()
+            .into_iter()
+            .flat_map(|suite| suite.cases.iter())
+            .collect()
+    }
+}
+
+/// Comparison of the test results of two builds
+///
+/// Test cases are matched by their suite, class name and name, and for aggregated reports by
+/// the configuration or module that ran them. Cases run several times with the same suite,
+/// class name and name are matched in order.
+#[derive(Debug, Clone, Default)]
+pub struct TestComparison {
+    /// Test cases failing in the compared build and passing or skipped in the base build
+    pub newly_failing: Vec<TestCase>,
+    /// Test cases passing in the compared build and failing in the base build
+    pub fixed: Vec<TestCase>,
+    /// Test cases failing in both builds, with their `age` in the compared build
+    pub still_failing: Vec<TestCase>,
+    /// Test cases only in the compared build, which may be failing
+    pub added: Vec<TestCase>,
+    /// Test cases only in the base build
+    pub removed: Vec<TestCase>,
+    /// Test cases skipped in the compared build and not in the base build
+    pub newly_skipped: Vec<TestCase>,
+}
+
+impl TestComparison {
+    /// Are there tests failing in the compared build that weren't failing in the base build
+    pub fn has_regressions(&self) -> bool {
+        !self.newly_failing.is_empty() || self.added.iter().any(TestCase::is_failing)
+    }
+}
+
+impl TestCase {
+    fn is_failing(&self) -> bool {
+        self.status.is_some_and(TestStatus::is_failure)
+    }
+
+    fn is_skipped(&self) -> bool {
+        self.skipped || self.status == Some(TestStatus::Skipped)
+    }
+}
+
+/// Identifier of a test case in a report: the build of the aggregated report, without its
+/// number, the name of its suite, its class name and its name
+type TestCaseKey<'a> = (&'a str, &'a str, &'a str, &'a str);
+
+impl TestReport {
+    /// Compare this report with the report of a `base` build
+    pub fn compare_with(&self, base: &TestReport) -> TestComparison {
+        let mut base_cases = base.cases_by_key("");
+        let mut comparison = TestComparison::default();
+        for (key, cases) in self.cases_by_key("") {
+            // Cases with the same key, like reruns of a suite, are matched in order
+            let mut same_key = base_cases.get_mut(&key);
+            for case in cases {
+                let case = case.clone();
+                let base_case = same_key
+                    .as_mut()
+                    .filter(|same_key| !same_key.is_empty())
+                    .map(|same_key| same_key.remove(0));
+                match base_case {
+                    None => comparison.added.push(case),
+                    Some(base_case) => match (base_case.is_failing(), case.is_failing()) {
+                        (true, true) => comparison.still_failing.push(case),
+                        (false, true) => comparison.newly_failing.push(case),
+                        _ if case.is_skipped() && !base_case.is_skipped() => {
+                            comparison.newly_skipped.push(case)
+                        }
+                        (true, false) if !case.is_skipped() => comparison.fixed.push(case),
+                        _ => (),
+                    },
+                }
+            }
+        }
+        comparison.removed = base_cases.into_values().flatten().cloned().collect();
+        comparison
+    }
+
+    fn cases_by_key<'a>(&'a self, child: &'a str) -> BTreeMap<TestCaseKey<'a>, Vec<&'a TestCase>> {
+        let mut cases: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for suite in &self.suites {
+            for case in &suite.cases {
+                let key = (
+                    child,
+                    suite.name.as_str(),
+                    case.class_name.as_str(),
+                    case.name.as_str(),
+                );
+                cases.entry(key).or_default().push(case);
+            }
+        }
+        for child_report in &self.child_reports {
+            let child = child_report
+                .child
+                .as_ref()
+                .map_or("", |build| without_build_number(&build.url));
+            for (key, child_cases) in child_report.result.cases_by_key(child) {
+                cases.entry(key).or_default().extend(child_cases);
+            }
+        }
+        cases
+    }
+}
+
+/// URL of a build without its number, to match the builds of the same configuration or module
+fn without_build_number(url: &str) -> &str {
+    let url = url.trim_end_matches('/');
+    url.rsplit_once('/').map_or(url, |(job_url, _)| job_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(cases: &[(&str, &str)]) -> TestReport {
+        serde_json::from_value(serde_json::json!({
+            "suites": [{
+                "name": "suite",
+                "cases": cases
+                    .iter()
+                    .map(|(name, status)| serde_json::json!({
+                        "className": "app.Test",
+                        "name": name,
+                        "status": status,
+                        "skipped": *status == "SKIPPED",
+                    }))
+                    .collect::<Vec<_>>(),
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn names(cases: &[TestCase]) -> Vec<&str> {
+        cases.iter().map(|case| case.name.as_str()).collect()
+    }
+
+    #[test]
+    fn can_sort_cases_when_comparing_reports() {
+        let base = report(&[
+            ("breaks", "PASSED"),
+            ("gets fixed", "FAILED"),
+            ("keeps failing", "FAILED"),
+            ("gets skipped", "PASSED"),
+            ("gets removed", "PASSED"),
+            ("keeps passing", "PASSED"),
+        ]);
+        let compared = report(&[
+            ("breaks", "REGRESSION"),
+            ("gets fixed", "FIXED"),
+            ("keeps failing", "FAILED"),
+            ("gets skipped", "SKIPPED"),
+            ("gets added", "FAILED"),
+            ("keeps passing", "PASSED"),
+        ]);
+
+        let comparison = compared.compare_with(&base);
+
+        assert_eq!(names(&comparison.newly_failing), vec!["breaks"]);
+        assert_eq!(names(&comparison.fixed), vec!["gets fixed"]);
+        assert_eq!(names(&comparison.still_failing), vec!["keeps failing"]);
+        assert_eq!(names(&comparison.newly_skipped), vec!["gets skipped"]);
+        assert_eq!(names(&comparison.added), vec!["gets added"]);
+        assert_eq!(names(&comparison.removed), vec!["gets removed"]);
+        assert!(comparison.has_regressions());
+        assert!(!base.compare_with(&base).has_regressions());
+    }
+
+    #[test]
+    fn can_match_cases_run_in_several_suites() {
+        let suites = |statuses: &[(&str, &str)]| -> TestReport {
+            serde_json::from_value(serde_json::json!({
+                "suites": statuses
+                    .iter()
+                    .map(|(suite, status)| serde_json::json!({
+                        "name": suite,
+                        "cases": [{"className": "app.Test", "name": "runs", "status": status}],
+                    }))
+                    .collect::<Vec<_>>(),
+            }))
+            .unwrap()
+        };
+
+        let comparison = suites(&[("unit", "PASSED"), ("rerun", "REGRESSION")])
+            .compare_with(&suites(&[("unit", "PASSED"), ("rerun", "PASSED")]));
+        assert_eq!(comparison.newly_failing.len(), 1);
+        assert!(comparison.added.is_empty());
+        assert!(comparison.removed.is_empty());
+
+        let comparison = suites(&[("suite", "PASSED"), ("suite", "FIXED")])
+            .compare_with(&suites(&[("suite", "PASSED"), ("suite", "FAILED")]));
+        assert_eq!(comparison.fixed.len(), 1);
+        assert!(comparison.newly_failing.is_empty());
+        assert!(comparison.added.is_empty());
+        assert!(comparison.removed.is_empty());
+
+        let comparison = suites(&[("suite", "PASSED")])
+            .compare_with(&suites(&[("suite", "PASSED"), ("suite", "FAILED")]));
+        assert_eq!(comparison.removed.len(), 1);
+    }
+
+    #[test]
+    fn can_match_cases_by_configuration_in_aggregated_reports() {
+        let aggregated = |number: u32, linux: &str, windows: &str| TestReport {
+            child_reports: vec![("os=linux", linux), ("os=windows", windows)]
+                .into_iter()
+                .map(|(configuration, status)| ChildTestReport {
+                    child: serde_json::from_value(serde_json::json!({
+                        "number": number,
+                        "url": format!("http://jenkins/job/matrix/{}/{}/", configuration, number),
+                    }))
+                    .unwrap(),
+                    result: report(&[("runs", status)]),
+                })
+                .collect(),
+            ..TestReport::default()
+        };
+
+        let comparison =
+            aggregated(2, "PASSED", "REGRESSION").compare_with(&aggregated(1, "PASSED", "PASSED"));
+
+        assert_eq!(comparison.newly_failing.len(), 1);
+        assert!(comparison.added.is_empty());
+        assert!(comparison.removed.is_empty());
+    }
+}
@@ -3,12 +3,21 @@
 use serde::{self, Deserialize};
 
 use crate::client_internals::path::{Name, Path as PrivatePath};
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
 use crate::client_internals::InternalAdvancedQueryParams;
+use crate::client_internals::JsonResponse;
 
 // pub use client_internals::path::Name;
 pub use crate::client_internals::AdvancedQuery;
+pub use crate::client_internals::Auth;
+pub use crate::client_internals::JenkinsException;
+pub use crate::client_internals::RetryPolicy;
+pub use crate::client_internals::Transport;
 pub use crate::client_internals::{error, Error, Result};
 pub use crate::client_internals::{TreeBuilder, TreeQueryParam};
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+pub use reqwest::{Certificate, Identity};
 
 use crate::build;
 
@@ -68,9 +77,9 @@ pub enum Path<'a> {
     },
 }
 
-impl<'a> Into<PrivatePath<'a>> for Path<'a> {
-    fn into(self) -> PrivatePath<'a> {
-        match self {
+impl<'a> From<Path<'a>> for PrivatePath<'a> {
+    fn from(val: Path<'a>) -> Self {
+        match val {
             Path::Home => PrivatePath::Home,
             Path::View { name } => PrivatePath::View {
                 name: Name::Name(name),
@@ -80,7 +89,7 @@ impl<'a> Into<PrivatePath<'a>> for Path<'a> {
                 configuration,
             } => PrivatePath::Job {
                 name: Name::Name(name),
-                configuration: configuration.map(|v| Name::Name(v)),
+                configuration: configuration.map(Name::Name),
             },
             Path::Build {
                 job_name,
@@ -89,7 +98,7 @@ impl<'a> Into<PrivatePath<'a>> for Path<'a> {
             } => PrivatePath::Build {
                 job_name: Name::Name(job_name),
                 number,
-                configuration: configuration.map(|v| Name::Name(v)),
+                configuration: configuration.map(Name::Name),
             },
             Path::Queue => PrivatePath::Queue,
             Path::QueueItem { id } => PrivatePath::QueueItem { id },
@@ -100,7 +109,7 @@ impl<'a> Into<PrivatePath<'a>> for Path<'a> {
             } => PrivatePath::MavenArtifactRecord {
                 job_name: Name::Name(job_name),
                 number,
-                configuration: configuration.map(|v| Name::Name(v)),
+                configuration: configuration.map(Name::Name),
             },
             Path::Computers => PrivatePath::Computers,
             Path::Computer { name } => PrivatePath::Computer {
@@ -166,11 +175,30 @@ impl super::Jenkins {
         Q: Into<Option<AdvancedQuery>>,
         for<'de> T: Deserialize<'de>,
     {
-        Ok(self
-            .get_with_params(
-                &object.into(),
-                parameters.into().map(InternalAdvancedQueryParams::from),
-            )?
-            .json()?)
+        self.get_with_params(
+            &object.into(),
+            parameters.into().map(InternalAdvancedQueryParams::from),
+        )?
+        .json_body()
+    }
+}
+
+#[cfg(feature = "async")]
+impl crate::AsyncJenkins {
+    /// Get a `Path` from Jenkins, specifying the depth or tree parameters
+    ///
+    /// See [`Jenkins::get_object_as`](../struct.Jenkins.html#method.get_object_as)
+    pub async fn get_object_as<Q, T>(&self, object: Path<'_>, parameters: Q) -> Result<T>
+    where
+        Q: Into<Option<AdvancedQuery>>,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get_with_params(
+            &object.into(),
+            parameters.into().map(InternalAdvancedQueryParams::from),
+        )
+        .await?
+        .json_body()
+        .await
     }
 }
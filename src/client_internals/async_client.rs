@@ -0,0 +1,477 @@
+//! Asynchronous Jenkins Client
+
+use std::fmt::Debug;
+use std::time::Duration;
+
+use log::{debug, warn};
+use reqwest::{
+    header::HeaderValue, header::CONTENT_TYPE, multipart::Form, Body, Client, RequestBuilder,
+    Response, StatusCode,
+};
+use serde::Serialize;
+
+use super::auth::Authenticator;
+use super::csrf::{Crumb, CrumbCache, CrumbState, NO_VALID_CRUMB};
+use super::path::{self, Path};
+use super::response::AsyncJsonResponse;
+use super::{Error, Result, RetryPolicy};
+
+/// Asynchronous client struct with the methods to query Jenkins
+///
+/// It is built with [`JenkinsBuilder::build_async`](struct.JenkinsBuilder.html#method.build_async),
+/// and must be used from within a tokio runtime.
+///
+/// ```rust
+///# extern crate jenkins_api;
+///#
+///# use jenkins_api::JenkinsBuilder;
+///#
+///# async fn example_function() -> Result<(), Box<dyn std::error::Error>> {
+///     let jenkins = JenkinsBuilder::new("http://localhost:8080")
+///         .with_user("user", Some("password"))
+///         .build_async()?;
+///
+///     let job = jenkins.get_job("job name").await?;
+///     let build = job.last_build.as_ref().unwrap().get_full_build_async(&jenkins).await?;
+///#     Ok(())
+///# }
+/// ```
+#[derive(Debug)]
+pub struct AsyncJenkins {
+    pub(super) url: String,
+    pub(super) public_url: Option<String>,
+    pub(super) client: Client,
+    pub(super) auth: Authenticator,
+    pub(crate) crumb: CrumbCache,
+    pub(super) retry_policy: Option<RetryPolicy>,
+    pub(crate) depth: u8,
+}
+
+impl AsyncJenkins {
+    pub(crate) fn url_api_json(&self, endpoint: &str) -> String {
+        format!("{}{}/api/json", self.url, endpoint)
+    }
+
+    pub(crate) fn url(&self, endpoint: &str) -> String {
+        format!("{}{}", self.url, endpoint)
+    }
+
+    pub(crate) fn url_to_path<'a>(&self, url: &'a str) -> Result<Path<'a>> {
+        path::url_to_path(&self.url, self.public_url.as_deref(), url)
+    }
+
+    async fn send(&self, request_builder: RequestBuilder) -> Result<Response> {
+        self.login().await?;
+        let request_builder = self.auth.authenticate_async(request_builder)?;
+        let query = request_builder.build()?;
+        debug!("sending {} {}", query.method(), query.url());
+        Ok(self.client.execute(query).await?)
+    }
+
+    /// Log in with the login form if needed, the session is then kept in cookies
+    async fn login(&self) -> Result<()> {
+        if let Some(form) = self.auth.login_form() {
+            let path = Path::Raw {
+                path: "/j_spring_security_check",
+            };
+            let request = self.client.post(&self.url(&path.to_string())).form(&form);
+            debug!("logging in to {}", self.url);
+            let response = Self::error_for_status(request.send().await?).await?;
+            self.auth.check_login(response.url().path())?;
+        }
+        Ok(())
+    }
+
+    async fn error_for_status(response: Response) -> Result<Response> {
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            warn!("got an error: {}", status);
+            let path = response.url().path().to_string();
+            let body = response.text().await?;
+            Err(Error::from_status(status, &path, &body))
+        } else {
+            Ok(response)
+        }
+    }
+
+    pub(crate) async fn get(&self, path: &Path<'_>) -> Result<Response> {
+        self.get_with_params(path, [("depth", &self.depth.to_string())])
+            .await
+    }
+
+    pub(crate) async fn get_with_params<T: Serialize>(
+        &self,
+        path: &Path<'_>,
+        qps: T,
+    ) -> Result<Response> {
+        let query = self
+            .client
+            .get(&self.url_api_json(&path.to_string()))
+            .query(&qps);
+        self.send_with_retry(query, false, true).await
+    }
+
+    pub(crate) async fn post_with_body<T: Into<Body> + Debug>(
+        &self,
+        path: &Path<'_>,
+        body: T,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        let mut request_builder = self.client.post(&self.url(&path.to_string()));
+
+        request_builder = request_builder.header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        debug!("{:?}", body);
+        request_builder = request_builder.query(qps).body(body);
+
+        self.send_with_retry(request_builder, true, false).await
+    }
+
+    pub(crate) async fn post_multipart(
+        &self,
+        path: &Path<'_>,
+        form: Form,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        let request_builder = self
+            .client
+            .post(&self.url(&path.to_string()))
+            .query(qps)
+            .multipart(form);
+        self.send_with_retry(request_builder, true, false).await
+    }
+
+    /// Send a request, retrying it according to the `RetryPolicy` of the client
+    ///
+    /// When `with_csrf` is set, a crumb is added to each attempt, and a new crumb is
+    /// fetched once if Jenkins rejects it.
+    ///
+    /// A client using the login form logs in again once if Jenkins replies with a 401 or a
+    /// 403, as its session may have expired.
+    async fn send_with_retry(
+        &self,
+        request_builder: RequestBuilder,
+        with_csrf: bool,
+        idempotent: bool,
+    ) -> Result<Response> {
+        let max_attempts = self
+            .retry_policy
+            .as_ref()
+            .map_or(1, |retry_policy| retry_policy.max_attempts(idempotent));
+        let mut attempt = 1;
+        let mut crumb_refreshed = false;
+        let mut logged_in_again = false;
+        let mut current = request_builder;
+        loop {
+            let next = current.try_clone();
+            let request = if with_csrf {
+                self.add_csrf_to_request(current).await?
+            } else {
+                current
+            };
+            let outcome = self.send(request).await;
+            let next = match next {
+                Some(next) => next,
+                None => return Self::error_for_status(outcome?).await,
+            };
+
+            if attempt < max_attempts && self.is_retryable(&outcome) {
+                let backoff = self
+                    .retry_policy
+                    .as_ref()
+                    .map_or(Duration::from_secs(0), |retry_policy| {
+                        retry_policy.backoff(attempt)
+                    });
+                warn!(
+                    "attempt {} of {} failed, retrying in {:?}",
+                    attempt, max_attempts, backoff
+                );
+                tokio::time::delay_for(backoff).await;
+                attempt += 1;
+                current = next;
+                continue;
+            }
+
+            let response = outcome?;
+            let status = response.status();
+            let crumb_rejected = with_csrf && !crumb_refreshed && status == StatusCode::FORBIDDEN;
+            // The session of the login form may have expired
+            let session_expired = !logged_in_again
+                && self.auth.is_logged_in()
+                && (status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN);
+            if crumb_rejected || session_expired {
+                let path = response.url().path().to_string();
+                let body = response.text().await?;
+                if crumb_rejected && body.contains(NO_VALID_CRUMB) {
+                    warn!("crumb rejected by Jenkins, getting a new one");
+                    self.crumb.invalidate();
+                    crumb_refreshed = true;
+                    current = next;
+                    continue;
+                }
+                if session_expired {
+                    warn!("got an error: {}, logging in again", status);
+                    self.auth.log_out();
+                    self.crumb.invalidate();
+                    logged_in_again = true;
+                    current = next;
+                    continue;
+                }
+                return Err(Error::from_status(status, &path, &body));
+            }
+            return Self::error_for_status(response).await;
+        }
+    }
+
+    fn is_retryable(&self, outcome: &Result<Response>) -> bool {
+        match (&self.retry_policy, outcome) {
+            (Some(retry_policy), Ok(response)) => {
+                retry_policy.is_retryable_status(response.status())
+            }
+            (Some(_), Err(Error::Transport(_))) => true,
+            _ => false,
+        }
+    }
+
+    pub(crate) async fn add_csrf_to_request(
+        &self,
+        request_builder: RequestBuilder,
+    ) -> Result<RequestBuilder> {
+        let crumb = match self.crumb.state() {
+            CrumbState::NotRequired => return Ok(request_builder),
+            CrumbState::Cached(crumb) => crumb,
+            CrumbState::Unknown => match self.get_csrf().await {
+                Ok(crumb) => {
+                    self.crumb.store(crumb.clone());
+                    crumb
+                }
+                Err(Error::NotFound { .. }) => {
+                    warn!("no crumb issuer found, disabling CSRF protection");
+                    self.crumb.disable();
+                    return Ok(request_builder);
+                }
+                Err(error) => return Err(error),
+            },
+        };
+        let (name, value) = crumb.header()?;
+        Ok(request_builder.header(name, value))
+    }
+
+    /// Get a crumb, without retrying as an async fn can't call itself through
+    /// `send_with_retry`
+    pub(crate) async fn get_csrf(&self) -> Result<Crumb> {
+        let query = self
+            .client
+            .get(&self.url_api_json(&Path::CrumbIssuer.to_string()))
+            .query(&[("depth", &self.depth.to_string())]);
+        let response = Self::error_for_status(self.send(query).await?).await?;
+        let crumb: Crumb = response.json_body().await?;
+        Ok(crumb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn futures_are_send() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://none:8080")
+            .build_async()
+            .unwrap();
+
+        assert_send(&jenkins_client.get_home());
+        assert_send(&jenkins_client.get_job("job"));
+        assert_send(&jenkins_client.get_build("job", 1));
+        assert_send(&jenkins_client.build_job("job", false));
+    }
+
+    #[tokio::test]
+    async fn can_post_with_body() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build_async()
+            .unwrap();
+
+        let _mock = mockito::mock("POST", "/async-mypath")
+            .with_body("ok")
+            .create();
+
+        let response = jenkins_client
+            .post_with_body(
+                &super::Path::Raw {
+                    path: "/async-mypath",
+                },
+                "body",
+                &[],
+            )
+            .await;
+
+        assert!(response.is_ok());
+        assert_eq!(response.unwrap().text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn can_post_with_body_and_get_error_state() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build_async()
+            .unwrap();
+
+        let _mock = mockito::mock("POST", "/async-error-IllegalStateException")
+            .with_status(500)
+            .with_body("hviqsuvnqsodjfsqjdgo java.lang.IllegalStateException: my error\nvzfjsd")
+            .create();
+
+        let response = jenkins_client
+            .post_with_body(
+                &super::Path::Raw {
+                    path: "/async-error-IllegalStateException",
+                },
+                "body",
+                &[],
+            )
+            .await;
+
+        assert!(response.is_err());
+        assert_eq!(
+            format!("{:?}", response),
+            r#"Err(IllegalState { message: "my error" })"#
+        );
+    }
+
+    #[tokio::test]
+    async fn can_post_with_csrf() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build_async()
+            .unwrap();
+
+        let _crumb = mockito::mock("GET", "/crumbIssuer/api/json?depth=1")
+            .with_body(r#"{"crumb":"abc","crumbRequestField":"Jenkins-Crumb"}"#)
+            .create();
+        let mock = mockito::mock("POST", "/async-with-crumb")
+            .match_header("jenkins-crumb", "abc")
+            .with_body("ok")
+            .create();
+
+        let response = jenkins_client
+            .post_with_body(
+                &super::Path::Raw {
+                    path: "/async-with-crumb",
+                },
+                "",
+                &[],
+            )
+            .await;
+
+        assert!(response.is_ok());
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn can_retry_get() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .with_retry_policy(
+                crate::client::RetryPolicy::default()
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(1)),
+            )
+            .build_async()
+            .unwrap();
+
+        let unavailable = mockito::mock("GET", "/job/async-flaky/api/json?depth=1")
+            .with_status(503)
+            .expect(2)
+            .create();
+        let _mock = mockito::mock("GET", "/job/async-flaky/api/json?depth=1")
+            .with_body(
+                r#"{"_class":"hudson.model.FreeStyleProject","name":"async-flaky",
+                "displayName":"async-flaky","url":"http://none/job/async-flaky/","actions":[]}"#,
+            )
+            .create();
+
+        let job = jenkins_client.get_job("async-flaky").await;
+
+        assert_eq!(job.unwrap().name, "async-flaky");
+        unavailable.assert();
+    }
+
+    #[tokio::test]
+    async fn doesnt_retry_post_by_default() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .with_retry_policy(
+                crate::client::RetryPolicy::default()
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(1)),
+            )
+            .build_async()
+            .unwrap();
+
+        let mock = mockito::mock("POST", "/async-flaky-post")
+            .with_status(503)
+            .expect(1)
+            .create();
+
+        let response = jenkins_client
+            .post_with_body(
+                &super::Path::Raw {
+                    path: "/async-flaky-post",
+                },
+                "",
+                &[],
+            )
+            .await;
+
+        match response {
+            Err(super::Error::ServerError { status, .. }) => assert_eq!(status, 503),
+            _ => panic!("expected a server error, got {:?}", response),
+        }
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn can_get_job() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build_async()
+            .unwrap();
+
+        let _mock = mockito::mock("GET", "/job/async%20job/api/json?depth=1")
+            .with_body(
+                r#"{"_class":"hudson.model.FreeStyleProject","name":"async job",
+                "displayName":"async job","url":"http://none/job/async%20job/","actions":[]}"#,
+            )
+            .create();
+
+        let job = jenkins_client.get_job("async job").await;
+
+        assert!(job.is_ok());
+        assert_eq!(job.unwrap().name, "async job");
+    }
+
+    #[tokio::test]
+    async fn can_build_job() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build_async()
+            .unwrap();
+
+        let mock = mockito::mock("POST", "/job/async-build/build?delay=5")
+            .with_status(201)
+            .with_header("location", "http://none/queue/item/4/")
+            .create();
+
+        let queue_item = jenkins_client
+            .job_builder("async-build", false)
+            .unwrap()
+            .with_delay(5)
+            .send()
+            .await;
+
+        assert!(queue_item.is_ok());
+        assert_eq!(queue_item.unwrap().url, "http://none/queue/item/4/");
+        mock.assert();
+    }
+}
@@ -1,8 +1,11 @@
+use std::sync::Mutex;
+
+use log::warn;
 use reqwest::{blocking::RequestBuilder, header::HeaderName, header::HeaderValue};
 use serde::Deserialize;
 
-use super::{path::Path, Jenkins};
-use crate::client::Result;
+use super::{path::Path, Jenkins, JsonResponse};
+use crate::client::{Error, Result};
 
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -11,24 +14,200 @@ pub(crate) struct Crumb {
     crumb_request_field: String,
 }
 
+impl Crumb {
+    /// Header to add to a request to pass CSRF protection
+    pub(crate) fn header(&self) -> Result<(HeaderName, HeaderValue)> {
+        Ok((
+            HeaderName::from_lowercase(self.crumb_request_field.to_lowercase().as_bytes())
+                .map_err(|_| Error::InvalidCrumbFieldName {
+                    field_name: self.crumb_request_field.clone(),
+                })?,
+            HeaderValue::from_str(&self.crumb).map_err(|_| Error::InvalidCrumb {
+                crumb: self.crumb.clone(),
+            })?,
+        ))
+    }
+}
+
+/// Body of the 403 response sent by Jenkins when a crumb is missing or stale
+pub(crate) const NO_VALID_CRUMB: &str = "No valid crumb";
+
+#[derive(Debug, Clone)]
+pub(crate) enum CrumbState {
+    /// No crumb has been requested yet, or the last one was rejected
+    Unknown,
+    /// Crumb to reuse for the next requests
+    Cached(Crumb),
+    /// CSRF protection is disabled
+    NotRequired,
+}
+
+/// Crumb shared by all the requests of a client
+///
+/// Crumbs are bound to the web session, which is kept in the cookie store of the client.
+#[derive(Debug)]
+pub(crate) struct CrumbCache(Mutex<CrumbState>);
+
+impl CrumbCache {
+    pub(crate) fn new(csrf_enabled: bool) -> Self {
+        CrumbCache(Mutex::new(if csrf_enabled {
+            CrumbState::Unknown
+        } else {
+            CrumbState::NotRequired
+        }))
+    }
+
+    pub(crate) fn state(&self) -> CrumbState {
+        self.lock().clone()
+    }
+
+    pub(crate) fn is_required(&self) -> bool {
+        !matches!(*self.lock(), CrumbState::NotRequired)
+    }
+
+    pub(crate) fn store(&self, crumb: Crumb) {
+        *self.lock() = CrumbState::Cached(crumb);
+    }
+
+    /// Forget the cached crumb, a new one will be requested before the next request
+    pub(crate) fn invalidate(&self) {
+        let mut state = self.lock();
+        if let CrumbState::Cached(_) = *state {
+            *state = CrumbState::Unknown;
+        }
+    }
+
+    /// Stop sending crumbs, Jenkins doesn't use them
+    pub(crate) fn disable(&self) {
+        *self.lock() = CrumbState::NotRequired;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CrumbState> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
 impl Jenkins {
     pub(crate) fn add_csrf_to_request(
         &self,
         request_builder: RequestBuilder,
     ) -> Result<RequestBuilder> {
-        if self.csrf_enabled {
-            let crumb = self.get_csrf()?;
-            Ok(request_builder.header(
-                HeaderName::from_lowercase(crumb.crumb_request_field.to_lowercase().as_bytes())?,
-                HeaderValue::from_str(&crumb.crumb)?,
-            ))
-        } else {
-            Ok(request_builder)
-        }
+        let crumb = match self.crumb.state() {
+            CrumbState::NotRequired => return Ok(request_builder),
+            CrumbState::Cached(crumb) => crumb,
+            CrumbState::Unknown => match self.get_csrf() {
+                Ok(crumb) => {
+                    self.crumb.store(crumb.clone());
+                    crumb
+                }
+                Err(Error::NotFound { .. }) => {
+                    warn!("no crumb issuer found, disabling CSRF protection");
+                    self.crumb.disable();
+                    return Ok(request_builder);
+                }
+                Err(error) => return Err(error),
+            },
+        };
+        let (name, value) = crumb.header()?;
+        Ok(request_builder.header(name, value))
     }
 
     pub(crate) fn get_csrf(&self) -> Result<Crumb> {
-        let crumb: Crumb = self.get(&Path::CrumbIssuer)?.json()?;
+        let crumb: Crumb = self.get(&Path::CrumbIssuer)?.json_body()?;
         Ok(crumb)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn can_reuse_crumb() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let crumb_mock = mockito::mock("GET", "/crumbIssuer/api/json?depth=1")
+            .with_body(r#"{"crumb":"abc","crumbRequestField":"Jenkins-Crumb"}"#)
+            .expect(1)
+            .create();
+        let post_mock = mockito::mock("POST", "/job/crumb-cached/polling")
+            .match_header("jenkins-crumb", "abc")
+            .expect(2)
+            .create();
+
+        assert!(jenkins_client.poll_scm_job("crumb-cached").is_ok());
+        assert!(jenkins_client.poll_scm_job("crumb-cached").is_ok());
+
+        crumb_mock.assert();
+        post_mock.assert();
+    }
+
+    #[test]
+    fn disables_csrf_without_crumb_issuer() {
+        let transport = std::sync::Arc::new(NoCrumbIssuer::default());
+        let jenkins_client = crate::JenkinsBuilder::new("http://none:8080")
+            .with_transport(transport.clone())
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.poll_scm_job("myjob").is_ok());
+        assert!(jenkins_client.poll_scm_job("myjob").is_ok());
+
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec![
+                "GET /crumbIssuer/api/json",
+                "POST /job/myjob/polling",
+                "POST /job/myjob/polling",
+            ]
+        );
+        assert!(!jenkins_client.crumb.is_required());
+    }
+
+    #[test]
+    fn disables_csrf_when_home_doesnt_use_crumbs() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let _mock = mockito::mock("GET", "/api/json?depth=1")
+            .with_body(
+                r#"{"mode":"NORMAL","nodeDescription":"master","nodeName":"","numExecutors":2,
+                "jobs":[],"quietingDown":false,"slaveAgentPort":0,"useCrumbs":false,
+                "useSecurity":true,"views":[]}"#,
+            )
+            .create();
+
+        assert!(jenkins_client.get_home().is_ok());
+        assert!(!jenkins_client.crumb.is_required());
+    }
+
+    #[derive(Debug, Default)]
+    struct NoCrumbIssuer {
+        requests: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl crate::client::Transport for NoCrumbIssuer {
+        fn execute(
+            &self,
+            request: reqwest::blocking::Request,
+        ) -> crate::client::Result<reqwest::blocking::Response> {
+            self.requests.lock().unwrap().push(format!(
+                "{} {}",
+                request.method(),
+                request.url().path()
+            ));
+            let status = match request.url().path() {
+                "/crumbIssuer/api/json" => 404,
+                _ => 200,
+            };
+            Ok(http::Response::builder()
+                .status(status)
+                .body("")
+                .unwrap()
+                .into())
+        }
+    }
+}
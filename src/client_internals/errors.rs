@@ -1,9 +1,12 @@
 use std::fmt;
 
+use log::warn;
 use thiserror::Error;
 
+use super::JenkinsException;
+
 /// Wrapper `Result` type
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+pub type Result<T> = std::result::Result<T, Error>;
 
 /// Errors that can be thrown
 #[derive(Debug, Error)]
@@ -17,6 +20,38 @@ pub enum Error {
         expected: ExpectedType,
     },
 
+    #[error("malformed url for a Jenkins object: {url}")]
+    ///  Error thrown when a link between objects can't be parsed
+    MalformedUrl {
+        /// URL found
+        url: String,
+    },
+
+    #[error("invalid Jenkins url '{url}': {source}")]
+    ///  Error thrown when the URL given to `JenkinsBuilder` can't be used
+    InvalidJenkinsUrl {
+        /// URL given
+        url: String,
+        /// Parsing error
+        source: url::ParseError,
+    },
+
+    #[error("invalid proxy url '{url}': {source}")]
+    ///  Error thrown when the proxy URL given to `JenkinsBuilder` can't be used
+    InvalidProxyUrl {
+        /// URL given
+        url: String,
+        /// Parsing error
+        source: url::ParseError,
+    },
+
+    #[error("invalid authentication header '{name}'")]
+    ///  Error thrown when an `Auth::Header` can't be sent as an HTTP header
+    InvalidAuthHeader {
+        /// Name of the header
+        name: String,
+    },
+
     #[error("invalid crumbfield '{field_name}', expected 'Jenkins-Crumb'")]
     ///  Error thrown when CSRF protection use an unexpected field name
     InvalidCrumbFieldName {
@@ -24,6 +59,13 @@ pub enum Error {
         field_name: String,
     },
 
+    #[error("invalid crumb '{crumb}'")]
+    ///  Error thrown when CSRF protection use a crumb that can't be sent as a header
+    InvalidCrumb {
+        /// Crumb provided by Jenkins api
+        crumb: String,
+    },
+
     #[error("illegal argument: '{message}'")]
     ///  Error thrown when building a parameterized job with an invalid parameter
     IllegalArgument {
@@ -38,10 +80,91 @@ pub enum Error {
         message: String,
     },
 
+    #[error("name already used: '{message}'")]
+    ///  Error thrown when creating, copying or renaming an item to a name already used
+    NameAlreadyUsed {
+        /// Failure message provided by Jenkins
+        message: String,
+    },
+
+    #[error("invalid name: '{message}'")]
+    ///  Error thrown when creating, copying or renaming an item to an invalid name
+    InvalidName {
+        /// Failure message provided by Jenkins
+        message: String,
+    },
+
+    #[error("invalid XML: {0}")]
+    ///  Error when a config.xml file can't be read or written
+    InvalidXml(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("I/O error on '{}': {source}", path.display())]
+    ///  Error when reading or writing a local file
+    Io {
+        /// Path of the file
+        path: std::path::PathBuf,
+        /// I/O error
+        source: std::io::Error,
+    },
+
     #[error("can't build a job remotely with parameters")]
     ///  Error when trying to remotely build a job with parameters
     UnsupportedBuildConfiguration,
 
+    #[error("can't serialize parameters: {0}")]
+    ///  Error when the parameters of a build can't be serialized
+    InvalidParameters(#[source] serde_urlencoded::ser::Error),
+
+    #[error("unknown parameter '{name}'")]
+    ///  Error when building a job with a parameter it doesn't define
+    UnknownParameter {
+        /// Name of the parameter
+        name: String,
+    },
+
+    #[error("invalid value for parameter '{name}': {message}")]
+    ///  Error when the value of a parameter doesn't match its definition in the job
+    InvalidParameterValue {
+        /// Name of the parameter
+        name: String,
+        /// Why the value is invalid
+        message: String,
+    },
+
+    #[error("queue item cancelled: {url}")]
+    ///  Error when waiting for a build whose queue item was cancelled
+    QueueItemCancelled {
+        /// URL of the queue item
+        url: String,
+    },
+
+    #[error("timed out after {timeout:?} waiting for {url}")]
+    ///  Error when waiting for a queue item or a build takes longer than the timeout
+    Timeout {
+        /// URL of the queue item or build
+        url: String,
+        /// Timeout reached
+        timeout: std::time::Duration,
+    },
+
+    #[error("checksum mismatch for artifact '{}': expected {expected}, got {actual}", path.display())]
+    ///  Error when a downloaded artifact doesn't match its fingerprint or its maven checksum
+    ArtifactChecksumMismatch {
+        /// Path of the downloaded file
+        path: std::path::PathBuf,
+        /// Expected MD5 checksum
+        expected: String,
+        /// MD5 checksum of the downloaded file
+        actual: String,
+    },
+
+    #[error("invalid artifact path: {path}")]
+    ///  Error when the relative path of an artifact points outside of the download directory
+    InvalidArtifactPath {
+        /// Relative path of the artifact
+        path: String,
+    },
+
     #[error("can't do '{action}' on a {object_type} of type {variant_name}")]
     ///  Error when trying to do an action on an object not supporting it
     InvalidObjectType {
@@ -52,6 +175,165 @@ pub enum Error {
         /// Action
         action: Action,
     },
+
+    #[error("not found: {path}{}", exception_suffix(.exception))]
+    ///  Jenkins replied with a 404 Not Found
+    NotFound {
+        /// Path requested
+        path: String,
+        /// Exception thrown by Jenkins, when it could be read from the response
+        exception: Option<JenkinsException>,
+    },
+
+    #[error("unauthorized: {path}{}", exception_suffix(.exception))]
+    ///  Jenkins replied with a 401 Unauthorized, credentials are missing or invalid
+    Unauthorized {
+        /// Path requested
+        path: String,
+        /// Exception thrown by Jenkins, when it could be read from the response
+        exception: Option<JenkinsException>,
+    },
+
+    #[error("forbidden: {path}{}", exception_suffix(.exception))]
+    ///  Jenkins replied with a 403 Forbidden, the user is missing a permission
+    Forbidden {
+        /// Path requested
+        path: String,
+        /// Exception thrown by Jenkins, when it could be read from the response
+        exception: Option<JenkinsException>,
+    },
+
+    #[error("conflict: {path}{}", exception_suffix(.exception))]
+    ///  Jenkins replied with a 409 Conflict
+    Conflict {
+        /// Path requested
+        path: String,
+        /// Exception thrown by Jenkins, when it could be read from the response
+        exception: Option<JenkinsException>,
+    },
+
+    #[error("client error {status}: {body_excerpt}")]
+    ///  Jenkins replied with another 4xx status
+    ClientError {
+        /// HTTP status
+        status: u16,
+        /// Beginning of the body of the response
+        body_excerpt: String,
+    },
+
+    #[error("server error {status}: {body_excerpt}")]
+    ///  Jenkins replied with a 5xx status
+    ServerError {
+        /// HTTP status
+        status: u16,
+        /// Beginning of the body of the response
+        body_excerpt: String,
+    },
+
+    #[error("exception on {path}: {exception}")]
+    ///  Jenkins replied with an error status caused by an exception
+    Exception {
+        /// Path requested
+        path: String,
+        /// HTTP status
+        status: u16,
+        /// Exception thrown by Jenkins
+        exception: JenkinsException,
+    },
+
+    #[error("transport error: {0}")]
+    ///  Error while sending a request or reading a response
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("can't deserialize response from {path}: {serde_error}")]
+    ///  Error when a response from Jenkins doesn't match the expected type
+    Deserialize {
+        /// Path requested
+        path: String,
+        /// Deserialization error
+        #[source]
+        serde_error: serde_json::Error,
+    },
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Transport(Box::new(error))
+    }
+}
+
+/// Maximum length of the body kept in an error
+const BODY_EXCERPT_LENGTH: usize = 500;
+
+impl Error {
+    /// Build the `Error` matching an HTTP error status returned by Jenkins for `path`
+    pub(crate) fn from_status(status: reqwest::StatusCode, path: &str, body: &str) -> Self {
+        let path = path.to_string();
+        let exception = JenkinsException::from_body(body);
+        match status.as_u16() {
+            401 => Error::Unauthorized { path, exception },
+            403 => Error::Forbidden { path, exception },
+            404 => Error::NotFound { path, exception },
+            409 => Error::Conflict { path, exception },
+            status => match exception {
+                Some(exception) => Error::from_exception(path, status, exception),
+                None => Error::from_status_and_body(status, body),
+            },
+        }
+    }
+
+    fn from_exception(path: String, status: u16, exception: JenkinsException) -> Self {
+        warn!("got an exception: {}", exception);
+        if let Some(cause) = exception.find("java.lang.IllegalStateException") {
+            return Error::IllegalState {
+                message: cause.message.clone().unwrap_or_default(),
+            };
+        }
+        if let Some(cause) = exception.find("java.lang.IllegalArgumentException") {
+            return Error::IllegalArgument {
+                message: cause.message.clone().unwrap_or_default(),
+            };
+        }
+        if exception.is_name_already_used() {
+            return Error::NameAlreadyUsed {
+                message: exception.message.unwrap_or_default(),
+            };
+        }
+        if exception.is_invalid_name() {
+            return Error::InvalidName {
+                message: exception.message.unwrap_or_default(),
+            };
+        }
+        Error::Exception {
+            path,
+            status,
+            exception,
+        }
+    }
+
+    fn from_status_and_body(status: u16, body: &str) -> Self {
+        match status {
+            status if status < 500 => Error::ClientError {
+                status,
+                body_excerpt: excerpt(body),
+            },
+            status => Error::ServerError {
+                status,
+                body_excerpt: excerpt(body),
+            },
+        }
+    }
+}
+
+fn exception_suffix(exception: &Option<JenkinsException>) -> String {
+    exception
+        .as_ref()
+        .map(|exception| format!(" ({})", exception))
+        .unwrap_or_default()
+}
+
+fn excerpt(body: &str) -> String {
+    body.chars().take(BODY_EXCERPT_LENGTH).collect()
 }
 
 /// Possible type of URL expected in links between items
@@ -0,0 +1,265 @@
+use std::fmt;
+
+use regex::Regex;
+
+/// An exception thrown by Jenkins while processing a request
+///
+/// It is read from the stack trace or the error page returned by Jenkins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JenkinsException {
+    /// Fully qualified class of the exception
+    pub class: String,
+    /// Message of the exception
+    pub message: Option<String>,
+    /// Exception that caused this one
+    pub cause: Option<Box<JenkinsException>>,
+}
+
+impl JenkinsException {
+    /// Iterate over this exception and its causes
+    pub fn chain(&self) -> impl Iterator<Item = &JenkinsException> {
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.cause.as_deref();
+            Some(current)
+        })
+    }
+
+    /// The deepest cause of this exception
+    pub fn root_cause(&self) -> &JenkinsException {
+        self.chain().last().unwrap_or(self)
+    }
+
+    /// Find an exception of class `class` in this exception and its causes
+    pub fn find(&self, class: &str) -> Option<&JenkinsException> {
+        self.chain().find(|exception| exception.class == class)
+    }
+
+    /// Read an exception from the body of an error response sent by Jenkins
+    pub(crate) fn from_body(body: &str) -> Option<Self> {
+        let text = unescape_html(body);
+        Self::from_stack_trace(&text).or_else(|| Self::from_failure(&text))
+    }
+
+    fn from_stack_trace(text: &str) -> Option<Self> {
+        let exception_re = Regex::new(
+            r"(?m)(?:^|\s)((?:[a-z_$][\w$]*\.)+[A-Z][\w$]*(?:Exception|Error|Failure|Throwable)\d*)(?::[ \t]*([^\r\n]*))?$",
+        )
+        .unwrap();
+        let caused_by_re = Regex::new(r"(?m)^\s*Caused by:\s*").unwrap();
+
+        let mut parts = caused_by_re.split(text);
+        let mut chain = vec![];
+        if let Some(captures) = parts.next().and_then(|part| exception_re.captures(part)) {
+            chain.push(Self::from_captures(&captures));
+        }
+        if chain.is_empty() {
+            return None;
+        }
+        for part in parts {
+            if let Some(captures) = exception_re.captures(part) {
+                chain.push(Self::from_captures(&captures));
+            }
+        }
+
+        chain.into_iter().rev().fold(None, |cause, mut exception| {
+            exception.cause = cause.map(Box::new);
+            Some(exception)
+        })
+    }
+
+    fn from_captures(captures: &regex::Captures) -> Self {
+        JenkinsException {
+            class: captures[1].to_string(),
+            message: captures
+                .get(2)
+                .map(|message| message.as_str().trim().to_string())
+                .filter(|message| !message.is_empty()),
+            cause: None,
+        }
+    }
+
+    /// Stapler and `hudson.model.Failure` error pages only have a message
+    fn from_failure(text: &str) -> Option<Self> {
+        let item_name_failures: Vec<String> = NAME_ALREADY_USED
+            .iter()
+            .chain(INVALID_NAME)
+            .map(|failure| regex::escape(failure))
+            .collect();
+        let failure_re = Regex::new(&format!(
+            r"(?m)No such (?:job|item|project)(?::[ \t]*[^\r\n]*)?|^[^\r\n]*(?:{})[^\r\n]*$",
+            item_name_failures.join("|")
+        ))
+        .unwrap();
+        failure_re.find(text).map(|failure| JenkinsException {
+            class: FAILURE_CLASS.to_string(),
+            message: Some(failure.as_str().trim().to_string()),
+            cause: None,
+        })
+    }
+
+    /// Is this a failure because an item with the same name already exists
+    pub(crate) fn is_name_already_used(&self) -> bool {
+        self.is_failure_with(NAME_ALREADY_USED)
+    }
+
+    /// Is this a failure because the name of an item is invalid
+    pub(crate) fn is_invalid_name(&self) -> bool {
+        self.is_failure_with(INVALID_NAME)
+    }
+
+    fn is_failure_with(&self, failures: &[&str]) -> bool {
+        self.class == FAILURE_CLASS
+            && self
+                .message
+                .as_deref()
+                .is_some_and(|message| failures.iter().any(|failure| message.contains(failure)))
+    }
+}
+
+/// Class of the exceptions Jenkins uses to report an error to the user
+const FAILURE_CLASS: &str = "hudson.model.Failure";
+
+/// Messages of failures when creating, copying or renaming to a name already used
+const NAME_ALREADY_USED: &[&str] = &["already exists with the name", "is already in use"];
+
+/// Messages of failures when creating, copying or renaming to an invalid name
+const INVALID_NAME: &[&str] = &[
+    "is an unsafe character",
+    "is not an allowed name",
+    "No name is specified",
+    "A name cannot end with",
+];
+
+impl fmt::Display for JenkinsException {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(ref message) => write!(f, "{}: {}", self.class, message)?,
+            None => write!(f, "{}", self.class)?,
+        }
+        if let Some(ref cause) = self.cause {
+            write!(f, ", caused by {}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+fn unescape_html(body: &str) -> String {
+    let tags_re = Regex::new(r"<[^>]*>").unwrap();
+    tags_re
+        .replace_all(body, "\n")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_exception_in_text() {
+        let exception = JenkinsException::from_body(
+            "hviqsuvnqsodjfsqjdgo java.lang.IllegalStateException: my error\nvzfjsd",
+        );
+
+        assert_eq!(
+            exception,
+            Some(JenkinsException {
+                class: "java.lang.IllegalStateException".to_string(),
+                message: Some("my error".to_string()),
+                cause: None,
+            })
+        );
+    }
+
+    #[test]
+    fn can_parse_stack_trace_with_causes() {
+        let exception = JenkinsException::from_body(
+            r#"<html><body><h2>Stack trace</h2><pre>javax.servlet.ServletException: hudson.AbortException: no workspace
+	at org.kohsuke.stapler.Stapler.tryInvoke(Stapler.java:796)
+	at org.kohsuke.stapler.Stapler.invoke(Stapler.java:876)
+Caused by: hudson.AbortException: no workspace
+	at hudson.model.AbstractProject.doBuild(AbstractProject.java:1789)
+Caused by: java.io.IOException
+	at hudson.FilePath.act(FilePath.java:1042)
+</pre></body></html>"#,
+        )
+        .unwrap();
+
+        assert_eq!(exception.class, "javax.servlet.ServletException");
+        assert_eq!(
+            exception.message.as_deref(),
+            Some("hudson.AbortException: no workspace")
+        );
+        assert_eq!(
+            exception
+                .chain()
+                .map(|exception| exception.class.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "javax.servlet.ServletException",
+                "hudson.AbortException",
+                "java.io.IOException"
+            ]
+        );
+        assert_eq!(exception.root_cause().class, "java.io.IOException");
+        assert_eq!(exception.root_cause().message, None);
+        assert!(exception.find("hudson.AbortException").is_some());
+    }
+
+    #[test]
+    fn can_parse_escaped_message() {
+        let exception = JenkinsException::from_body(
+            "<pre>jenkins.security.NotReallyRoleSensitiveCallableException: can&#39;t use &lt;this&gt;\n</pre>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            exception.class,
+            "jenkins.security.NotReallyRoleSensitiveCallableException"
+        );
+        assert_eq!(exception.message.as_deref(), Some("can't use <this>"));
+    }
+
+    #[test]
+    fn can_parse_no_such_job() {
+        let exception = JenkinsException::from_body(
+            "<html><body><h1>Error</h1><p>No such job: my-job</p></body></html>",
+        )
+        .unwrap();
+
+        assert_eq!(exception.class, "hudson.model.Failure");
+        assert_eq!(exception.message.as_deref(), Some("No such job: my-job"));
+    }
+
+    #[test]
+    fn can_parse_item_name_failures() {
+        let exception = JenkinsException::from_body(
+            "<html><body><h1>Error</h1><p>A job already exists with the name ‘my-job’</p></body></html>",
+        )
+        .unwrap();
+        assert_eq!(exception.class, "hudson.model.Failure");
+        assert_eq!(
+            exception.message.as_deref(),
+            Some("A job already exists with the name ‘my-job’")
+        );
+        assert!(exception.is_name_already_used());
+        assert!(!exception.is_invalid_name());
+
+        let exception = JenkinsException::from_body("<p>‘?’ is an unsafe character</p>").unwrap();
+        assert!(exception.is_invalid_name());
+    }
+
+    #[test]
+    fn doesnt_parse_other_bodies() {
+        assert_eq!(JenkinsException::from_body(""), None);
+        assert_eq!(
+            JenkinsException::from_body("<html><body>Oops! A problem occurred.</body></html>"),
+            None
+        );
+    }
+}
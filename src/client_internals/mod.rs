@@ -4,23 +4,37 @@ use std::fmt::Debug;
 use std::string::ToString;
 
 use log::{debug, warn};
-use regex::Regex;
 use reqwest::{
-    blocking::{Body, Client, RequestBuilder, Response},
+    blocking::{multipart::Form, Body, Client, RequestBuilder, Response},
     header::HeaderValue,
-    header::CONTENT_TYPE,
-    StatusCode,
+    header::{CONTENT_TYPE, RANGE},
 };
 use serde::Serialize;
 
+mod auth;
+pub use self::auth::Auth;
 mod errors;
 pub use self::errors::{Error, Result};
+mod exception;
+pub use self::exception::JenkinsException;
 mod builder;
 pub mod path;
 pub use self::builder::JenkinsBuilder;
 pub use self::path::{Name, Path};
+#[cfg(feature = "async")]
+mod async_client;
 mod csrf;
+mod response;
+mod retry;
+#[cfg(feature = "async")]
+pub(crate) use self::response::AsyncJsonResponse;
+pub(crate) use self::response::JsonResponse;
+pub use self::retry::RetryPolicy;
+mod transport;
 mod tree;
+#[cfg(feature = "async")]
+pub use self::async_client::AsyncJenkins;
+pub use self::transport::Transport;
 pub use self::tree::{TreeBuilder, TreeQueryParam};
 
 /// Helper type for error management
@@ -29,19 +43,17 @@ pub mod error {
     pub use super::errors::ExpectedType;
 }
 
-#[derive(Debug, PartialEq)]
-struct User {
-    username: String,
-    password: Option<String>,
-}
-
 /// Client struct with the methods to query Jenkins
 #[derive(Debug)]
 pub struct Jenkins {
     url: String,
+    public_url: Option<String>,
     client: Client,
-    user: Option<User>,
-    csrf_enabled: bool,
+    transport: Box<dyn Transport>,
+    auth: auth::Authenticator,
+    pub(crate) crumb: csrf::CrumbCache,
+    retry_policy: Option<RetryPolicy>,
+    timeout: Option<std::time::Duration>,
     pub(crate) depth: u8,
 }
 
@@ -77,6 +89,9 @@ impl From<AdvancedQuery> for InternalAdvancedQueryParams {
     }
 }
 
+/// Empty query parameters, for requests that don't need any
+pub(crate) const NO_PARAMS: [(&str, &str); 0] = [];
+
 impl Jenkins {
     pub(crate) fn url_api_json(&self, endpoint: &str) -> String {
         format!("{}{}/api/json", self.url, endpoint)
@@ -86,26 +101,45 @@ impl Jenkins {
         format!("{}{}", self.url, endpoint)
     }
 
-    fn send(&self, mut request_builder: RequestBuilder) -> Result<Response> {
-        if let Some(ref user) = self.user {
-            request_builder =
-                request_builder.basic_auth(user.username.clone(), user.password.clone());
+    fn send(&self, request_builder: RequestBuilder) -> Result<Response> {
+        self.login()?;
+        let mut request_builder = self.auth.authenticate(request_builder)?;
+        if let Some(timeout) = self.timeout {
+            request_builder = request_builder.timeout(timeout);
         }
         let query = request_builder.build()?;
         debug!("sending {} {}", query.method(), query.url());
-        Ok(self.client.execute(query)?)
+        self.transport.execute(query)
+    }
+
+    /// Log in with the login form if needed, the session is then kept in cookies
+    fn login(&self) -> Result<()> {
+        if let Some(form) = self.auth.login_form() {
+            let path = Path::Raw {
+                path: "/j_spring_security_check",
+            };
+            let request = self.client.post(&self.url(&path.to_string())).form(&form);
+            debug!("logging in to {}", self.url);
+            let response = Self::error_for_status(self.transport.execute(request.build()?)?)?;
+            self.auth.check_login(response.url().path())?;
+        }
+        Ok(())
     }
 
     fn error_for_status(response: Response) -> Result<Response> {
         let status = response.status();
         if status.is_client_error() || status.is_server_error() {
             warn!("got an error: {}", status);
+            let path = response.url().path().to_string();
+            let body = response.text()?;
+            Err(Error::from_status(status, &path, &body))
+        } else {
+            Ok(response)
         }
-        Ok(response.error_for_status()?)
     }
 
     pub(crate) fn get(&self, path: &Path) -> Result<Response> {
-        self.get_with_params(path, &[("depth", &self.depth.to_string())])
+        self.get_with_params(path, [("depth", &self.depth.to_string())])
     }
 
     pub(crate) fn get_with_params<T: Serialize>(&self, path: &Path, qps: T) -> Result<Response> {
@@ -113,15 +147,41 @@ impl Jenkins {
             .client
             .get(&self.url_api_json(&path.to_string()))
             .query(&qps);
-        Ok(Self::error_for_status(self.send(query)?)?)
+        self.send_with_retry(query, false, true)
     }
 
-    pub(crate) fn post(&self, path: &Path) -> Result<Response> {
-        let mut request_builder = self.client.post(&self.url(&path.to_string()));
+    /// Send a GET request to the path itself instead of its JSON API
+    pub(crate) fn get_raw<T: Serialize>(&self, path: &Path, qps: T) -> Result<Response> {
+        let query = self.client.get(&self.url(&path.to_string())).query(&qps);
+        self.send_with_retry(query, false, true)
+    }
+
+    /// Send a GET request to the path itself, asking for its content from the byte `start`
+    pub(crate) fn get_raw_from(&self, path: &Path, start: u64) -> Result<Response> {
+        let query = self
+            .client
+            .get(&self.url(&path.to_string()))
+            .header(RANGE, format!("bytes={}-", start));
+        self.send_with_retry(query, false, true)
+    }
 
-        request_builder = self.add_csrf_to_request(request_builder)?;
+    /// Send a GET request that triggers an action, and is only retried if allowed by the `RetryPolicy`
+    pub(crate) fn trigger_with_params<T: Serialize>(
+        &self,
+        path: &Path,
+        qps: T,
+    ) -> Result<Response> {
+        let query = self
+            .client
+            .get(&self.url_api_json(&path.to_string()))
+            .query(&qps);
+        self.send_with_retry(query, false, false)
+    }
 
-        Ok(Self::error_for_status(self.send(request_builder)?)?)
+    pub(crate) fn post(&self, path: &Path) -> Result<Response> {
+        let request_builder = self.client.post(&self.url(&path.to_string()));
+
+        self.send_with_retry(request_builder, self.crumb.is_required(), false)
     }
 
     pub(crate) fn post_with_body<T: Into<Body> + Debug>(
@@ -132,68 +192,42 @@ impl Jenkins {
     ) -> Result<Response> {
         let mut request_builder = self.client.post(&self.url(&path.to_string()));
 
-        request_builder = self.add_csrf_to_request(request_builder)?;
-
         request_builder = request_builder.header(
             CONTENT_TYPE,
             HeaderValue::from_static("application/x-www-form-urlencoded"),
         );
         debug!("{:?}", body);
         request_builder = request_builder.query(qps).body(body);
-        let response = self.send(request_builder)?;
-
-        if response.status() == StatusCode::INTERNAL_SERVER_ERROR {
-            // get the error before reading the body. In this case it can't be OK
-            let error = match response.error_for_status_ref() {
-                Ok(_) => unreachable!(),
-                Err(err) => err,
-            };
+        self.send_with_retry(request_builder, self.crumb.is_required(), false)
+    }
 
-            let body = response.text()?;
+    pub(crate) fn post_multipart(
+        &self,
+        path: &Path,
+        form: Form,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        let request_builder = self
+            .client
+            .post(&self.url(&path.to_string()))
+            .query(qps)
+            .multipart(form);
+        self.send_with_retry(request_builder, self.crumb.is_required(), false)
+    }
 
-            let re = Regex::new(r"java.lang.([a-zA-Z]+): (.*)").unwrap();
-            if let Some(captures) = re.captures(&body) {
-                match captures.get(1).map(|v| v.as_str()) {
-                    Some("IllegalStateException") => {
-                        warn!(
-                            "got an IllegalState error: {}",
-                            captures.get(0).map(|v| v.as_str()).unwrap_or("unspecified")
-                        );
-                        Err(Error::IllegalState {
-                            message: captures
-                                .get(2)
-                                .map(|v| v.as_str())
-                                .unwrap_or("no message")
-                                .to_string(),
-                        })
-                    }
-                    Some("IllegalArgumentException") => {
-                        warn!(
-                            "got an IllegalArgument error: {}",
-                            captures.get(0).map(|v| v.as_str()).unwrap_or("unspecified")
-                        );
-                        Err(Error::IllegalArgument {
-                            message: captures
-                                .get(2)
-                                .map(|v| v.as_str())
-                                .unwrap_or("no message")
-                                .to_string(),
-                        })
-                    }
-                    Some(_) => {
-                        warn!(
-                            "got an Unknwon error: {}",
-                            captures.get(0).map(|v| v.as_str()).unwrap_or("unspecified")
-                        );
-                        Ok(())
-                    }
-                    _ => Ok(()),
-                }?;
-            }
-            Err(error.into())
-        } else {
-            Ok(Self::error_for_status(response)?)
-        }
+    pub(crate) fn post_xml(
+        &self,
+        path: &Path,
+        body: &str,
+        qps: &[(&str, &str)],
+    ) -> Result<Response> {
+        let request_builder = self
+            .client
+            .post(&self.url(&path.to_string()))
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/xml"))
+            .query(qps)
+            .body(body.to_string());
+        self.send_with_retry(request_builder, self.crumb.is_required(), false)
     }
 }
 
@@ -293,10 +327,48 @@ mod tests {
         assert!(response.is_err());
         assert_eq!(
             format!("{:?}", response),
-            r#"Err(reqwest::Error { kind: Status(500), url: Url { scheme: "http", host: Some(Ipv4(127.0.0.1)), port: Some(1234), path: "/error-NewException", query: None, fragment: None } })"#,
+            r#"Err(Exception { path: "/error-NewException", status: 500, exception: JenkinsException { class: "java.lang.NewException", message: Some("my error"), cause: None } })"#,
         );
     }
 
+    #[test]
+    fn can_get_error_with_exception() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let _mock = mockito::mock("GET", "/job/broken/api/json?depth=1")
+            .with_status(500)
+            .with_body(
+                "<html><body><h2>Stack trace</h2><pre>javax.servlet.ServletException: hudson.AbortException: no workspace\n\
+                 \tat org.kohsuke.stapler.Stapler.tryInvoke(Stapler.java:796)\n\
+                 Caused by: hudson.AbortException: no workspace\n\
+                 \tat hudson.model.AbstractProject.doBuild(AbstractProject.java:1789)\n\
+                 </pre></body></html>",
+            )
+            .create();
+
+        let response = jenkins_client.get_job("broken");
+
+        match response {
+            Err(super::Error::Exception {
+                path,
+                status,
+                exception,
+            }) => {
+                assert_eq!(path, "/job/broken/api/json");
+                assert_eq!(status, 500);
+                assert_eq!(exception.class, "javax.servlet.ServletException");
+                assert_eq!(exception.root_cause().class, "hudson.AbortException");
+                assert_eq!(
+                    exception.root_cause().message.as_deref(),
+                    Some("no workspace")
+                );
+            }
+            _ => panic!("expected an exception, got {:?}", response),
+        }
+    }
+
     #[test]
     fn can_post_with_query_params() {
         let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
@@ -318,4 +390,1498 @@ mod tests {
         assert_eq!(response.unwrap().text().unwrap(), "ok");
         mock.assert()
     }
+
+    #[test]
+    fn can_get_error_not_found() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let _mock = mockito::mock("GET", "/job/not-found/api/json?depth=1")
+            .with_status(404)
+            .create();
+
+        let response = jenkins_client.get_job("not-found");
+
+        assert!(response.is_err());
+        assert_eq!(
+            format!("{:?}", response),
+            r#"Err(NotFound { path: "/job/not-found/api/json", exception: None })"#
+        );
+    }
+
+    #[test]
+    fn can_get_error_forbidden() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let _mock = mockito::mock("GET", "/job/forbidden/api/json?depth=1")
+            .with_status(403)
+            .create();
+
+        let response = jenkins_client.get_job("forbidden");
+
+        assert!(response.is_err());
+        assert_eq!(
+            format!("{:?}", response),
+            r#"Err(Forbidden { path: "/job/forbidden/api/json", exception: None })"#
+        );
+    }
+
+    #[test]
+    fn can_get_exception_of_error_status() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let _mock_not_found = mockito::mock("GET", "/job/missing/api/json?depth=1")
+            .with_status(404)
+            .with_body("<html><body><h1>Error</h1><p>No such job: missing</p></body></html>")
+            .create();
+        let _mock_forbidden = mockito::mock("GET", "/job/secret/api/json?depth=1")
+            .with_status(403)
+            .with_body(
+                "<html><body><h2>HTTP ERROR 403</h2><pre>hudson.security.AccessDeniedException3: alice is missing the Job/Read permission\n\
+                 \tat hudson.security.ACL.checkPermission(ACL.java:73)\n\
+                 </pre></body></html>",
+            )
+            .create();
+
+        let response = jenkins_client.get_job("missing");
+        match response {
+            Err(super::Error::NotFound {
+                path,
+                exception: Some(exception),
+            }) => {
+                assert_eq!(path, "/job/missing/api/json");
+                assert_eq!(exception.class, "hudson.model.Failure");
+                assert_eq!(exception.message.as_deref(), Some("No such job: missing"));
+            }
+            _ => panic!(
+                "expected a not found error with an exception, got {:?}",
+                response
+            ),
+        }
+
+        let response = jenkins_client.get_job("secret");
+        match response {
+            Err(super::Error::Forbidden {
+                path,
+                exception: Some(exception),
+            }) => {
+                assert_eq!(path, "/job/secret/api/json");
+                assert_eq!(exception.class, "hudson.security.AccessDeniedException3");
+                assert_eq!(
+                    exception.message.as_deref(),
+                    Some("alice is missing the Job/Read permission")
+                );
+            }
+            _ => panic!(
+                "expected a forbidden error with an exception, got {:?}",
+                response
+            ),
+        }
+    }
+
+    #[test]
+    fn can_get_error_deserialize() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .build()
+            .unwrap();
+
+        let _mock = mockito::mock("GET", "/job/invalid-json/api/json?depth=1")
+            .with_body(r#"{"name": 12}"#)
+            .create();
+
+        let response = jenkins_client.get_job("invalid-json");
+
+        match response {
+            Err(super::Error::Deserialize { path, .. }) => {
+                assert_eq!(path, "/job/invalid-json/api/json")
+            }
+            _ => panic!("expected a deserialize error, got {:?}", response),
+        }
+    }
+
+    #[test]
+    fn can_act_on_job_in_folders() {
+        use crate::job::Job;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _get_mock = mockito::mock("GET", "/job/team/job/service/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"name": "service", "displayName": "service", "actions": [], "url": "{}/job/team/job/service/"}}"#,
+                mockito::server_url()
+            ))
+            .create();
+        let enable_mock = mockito::mock("POST", "/job/team/job/service/enable")
+            .with_status(200)
+            .create();
+        let build_mock = mockito::mock("POST", "/job/team/job/service/build")
+            .with_status(201)
+            .with_header("Location", "http://none/queue/item/1/")
+            .create();
+
+        let job = jenkins_client.get_job("team/service").unwrap();
+        job.enable(&jenkins_client).unwrap();
+        let queue_item = crate::job::builder::JobBuilder::new(&job, &jenkins_client)
+            .unwrap()
+            .send()
+            .unwrap();
+
+        enable_mock.assert();
+        build_mock.assert();
+        assert_eq!(queue_item.url, "http://none/queue/item/1/");
+    }
+
+    #[test]
+    fn can_use_full_job_names() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build_mock = mockito::mock("POST", "/job/team/job/service/job/deploy%20job/build")
+            .with_status(201)
+            .with_header("Location", "http://none/queue/item/2/")
+            .create();
+        let get_build_mock = mockito::mock("GET", "/job/team/job/deploy/3/api/json?depth=1")
+            .with_status(404)
+            .create();
+
+        let queue_item = jenkins_client.build_job("team/service/deploy job", false);
+        let build = jenkins_client.get_build("team/deploy", 3);
+
+        build_mock.assert();
+        get_build_mock.assert();
+        assert_eq!(queue_item.unwrap().url, "http://none/queue/item/2/");
+        assert!(build.is_err());
+    }
+
+    #[test]
+    fn can_create_job_in_folder() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let create_mock = mockito::mock("POST", "/job/team/createItem")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "name".to_string(),
+                "my job".to_string(),
+            ))
+            .match_header("content-type", "application/xml")
+            .match_body("<project/>")
+            .create();
+
+        jenkins_client
+            .create_job("team/my job", "<project/>")
+            .unwrap();
+
+        create_mock.assert();
+    }
+
+    #[test]
+    fn can_get_error_copying_to_existing_job() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let copy_mock = mockito::mock("POST", "/createItem")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("name".to_string(), "copy".to_string()),
+                mockito::Matcher::UrlEncoded("mode".to_string(), "copy".to_string()),
+                mockito::Matcher::UrlEncoded("from".to_string(), "/team/original".to_string()),
+            ]))
+            .with_status(400)
+            .with_body("<html><body><h1>Error</h1><p>A job already exists with the name ‘copy’</p></body></html>")
+            .create();
+
+        let response = jenkins_client.copy_job("team/original", "copy");
+
+        copy_mock.assert();
+        assert_eq!(
+            format!("{:?}", response),
+            r#"Err(NameAlreadyUsed { message: "A job already exists with the name ‘copy’" })"#
+        );
+    }
+
+    #[test]
+    fn can_rename_update_and_delete_job_in_folder() {
+        use crate::job::Job;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _get_mock = mockito::mock("GET", "/job/team/job/old/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"name": "old", "displayName": "old", "actions": [], "url": "{}/job/team/job/old/"}}"#,
+                mockito::server_url()
+            ))
+            .create();
+        let config_mock = mockito::mock("POST", "/job/team/job/old/config.xml")
+            .match_body("<project/>")
+            .create();
+        let rename_mock = mockito::mock("POST", "/job/team/job/old/doRename?newName=new").create();
+        let delete_mock = mockito::mock("POST", "/job/team/job/old/doDelete").create();
+
+        let job = jenkins_client.get_job("team/old").unwrap();
+        job.update_config_xml(&jenkins_client, "<project/>")
+            .unwrap();
+        job.rename(&jenkins_client, "new").unwrap();
+        job.delete(&jenkins_client).unwrap();
+
+        config_mock.assert();
+        rename_mock.assert();
+        delete_mock.assert();
+    }
+
+    #[test]
+    fn can_get_error_for_invalid_name() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _mock = mockito::mock("POST", "/createItem?name=a%3Fb")
+            .with_status(400)
+            .with_body("<p>‘?’ is an unsafe character</p>")
+            .create();
+
+        match jenkins_client.create_job("a?b", "<project/>") {
+            Err(super::Error::InvalidName { message }) => {
+                assert_eq!(message, "‘?’ is an unsafe character")
+            }
+            other => panic!("expected an invalid name error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn can_walk_jobs_in_nested_folders() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let url = mockito::server_url();
+        let folder = "com.cloudbees.hudson.plugins.folder.Folder";
+        let _home_mock = mockito::mock("GET", "/api/json")
+            .match_query(mockito::Matcher::Regex(
+                "tree=jobs%5B_class%2CfullName%2Curl%2Cjobs".to_string(),
+            ))
+            .with_body(format!(
+                r#"{{"jobs": [
+                    {{"_class": "hudson.model.FreeStyleProject", "fullName": "top", "url": "{url}/job/top/"}},
+                    {{"_class": "{folder}", "fullName": "a", "url": "{url}/job/a/", "jobs": [
+                        {{"_class": "{folder}", "fullName": "a/b", "url": "{url}/job/a/job/b/", "jobs": [
+                            {{"_class": "{folder}", "fullName": "a/b/c", "url": "{url}/job/a/job/b/job/c/", "jobs": [
+                                {{"_class": "{folder}", "fullName": "a/b/c/d", "url": "{url}/job/a/job/b/job/c/job/d/", "jobs": [
+                                    {{"_class": "org.jenkinsci.plugins.workflow.job.WorkflowJob"}}
+                                ]}},
+                                {{"_class": "{folder}", "fullName": "a/b/c/empty", "url": "{url}/job/a/job/b/job/c/job/empty/", "jobs": []}}
+                            ]}}
+                        ]}}
+                    ]}}
+                ]}}"#,
+                url = url,
+                folder = folder,
+            ))
+            .create();
+        let folder_mock = mockito::mock("GET", "/job/a/job/b/job/c/job/d/api/json")
+            .match_query(mockito::Matcher::Regex("tree=jobs".to_string()))
+            .with_body(format!(
+                r#"{{"_class": "{folder}", "jobs": [
+                    {{"_class": "org.jenkinsci.plugins.workflow.job.WorkflowJob", "fullName": "a/b/c/d/deep job", "url": "{url}/job/a/job/b/job/c/job/d/job/deep%20job/"}}
+                ]}}"#,
+                url = url,
+                folder = folder,
+            ))
+            .create();
+
+        let jobs = jenkins_client
+            .walk_jobs()
+            .collect::<super::Result<Vec<_>>>()
+            .unwrap();
+
+        folder_mock.assert();
+        assert_eq!(
+            jobs.iter()
+                .map(|job| job.full_name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "top",
+                "a",
+                "a/b",
+                "a/b/c",
+                "a/b/c/d",
+                "a/b/c/empty",
+                "a/b/c/d/deep job"
+            ]
+        );
+        assert_eq!(
+            jobs[6].class.as_deref(),
+            Some("org.jenkinsci.plugins.workflow.job.WorkflowJob")
+        );
+    }
+
+    #[test]
+    fn can_create_folder_and_move_job() {
+        use crate::job::Job;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let create_mock = mockito::mock("POST", "/job/team/createItem")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("name".to_string(), "new folder".to_string()),
+                mockito::Matcher::UrlEncoded(
+                    "mode".to_string(),
+                    "com.cloudbees.hudson.plugins.folder.Folder".to_string(),
+                ),
+            ]))
+            .create();
+        let _get_job_mock = mockito::mock("GET", "/job/team/job/my%20job/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"name": "my job", "displayName": "my job", "actions": [], "url": "{}/job/team/job/my%20job/"}}"#,
+                mockito::server_url()
+            ))
+            .create();
+        let move_mock = mockito::mock("POST", "/job/team/job/my%20job/move/move")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "destination".to_string(),
+                "/team/new folder".to_string(),
+            ))
+            .create();
+
+        jenkins_client.create_folder("team/new folder").unwrap();
+        let job = jenkins_client.get_job("team/my job").unwrap();
+        job.move_to(&jenkins_client, "team/new folder").unwrap();
+
+        create_mock.assert();
+        move_mock.assert();
+    }
+
+    #[test]
+    fn can_list_and_get_branches_of_multibranch_project() {
+        use crate::job::{ComputedFolder, WorkflowMultiBranchProject};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let url = mockito::server_url();
+        let _get_project_mock = mockito::mock("GET", "/job/team/job/project/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"_class": "org.jenkinsci.plugins.workflow.multibranch.WorkflowMultiBranchProject",
+                    "name": "project", "displayName": "project", "actions": [], "jobs": [],
+                    "url": "{}/job/team/job/project/"}}"#,
+                url
+            ))
+            .create();
+        let _branches_mock = mockito::mock("GET", "/job/team/job/project/api/json")
+            .match_query(mockito::Matcher::Regex("tree=jobs".to_string()))
+            .with_body(format!(
+                r#"{{"jobs": [
+                    {{"_class": "org.jenkinsci.plugins.workflow.job.WorkflowJob",
+                      "name": "feature%2Fone", "displayName": "feature/one", "color": "blue",
+                      "url": "{url}/job/team/job/project/job/feature%252Fone/",
+                      "property": [{{"_class": "org.jenkinsci.plugins.workflow.multibranch.BranchJobProperty", "branch": {{"name": "feature/one"}}}}],
+                      "actions": [{{"_class": "jenkins.scm.api.metadata.ObjectMetadataAction", "objectUrl": "https://example.com/tree/feature/one"}}, {{}}]}},
+                    {{"_class": "org.jenkinsci.plugins.workflow.job.WorkflowJob",
+                      "name": "PR-3", "displayName": "PR-3 - Fix", "color": "red",
+                      "url": "{url}/job/team/job/project/job/PR-3/",
+                      "property": [{{"_class": "org.jenkinsci.plugins.workflow.multibranch.BranchJobProperty", "branch": {{"name": "PR-3"}}}}],
+                      "actions": [
+                        {{"_class": "jenkins.scm.api.metadata.ContributorMetadataAction", "contributor": "octocat"}},
+                        {{"_class": "jenkins.scm.api.actions.ChangeRequestAction", "id": "3", "title": "Fix", "URL": "https://example.com/pull/3"}}
+                      ]}}
+                ]}}"#,
+                url = url
+            ))
+            .create();
+        let branch_job_mock = mockito::mock(
+            "GET",
+            "/job/team/job/project/job/feature%252Fone/api/json?depth=1",
+        )
+        .with_body(format!(
+            r#"{{"name": "feature%2Fone", "displayName": "feature/one", "actions": [],
+                        "url": "{}/job/team/job/project/job/feature%252Fone/"}}"#,
+            url
+        ))
+        .create();
+        let scan_mock = mockito::mock("POST", "/job/team/job/project/build")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "delay".to_string(),
+                "0".to_string(),
+            ))
+            .create();
+        let _scan_log_mock = mockito::mock(
+            "GET",
+            "/job/team/job/project/computation/consoleText/api/json?depth=1",
+        )
+        .with_body("Finished: SUCCESS")
+        .create();
+
+        let project = jenkins_client
+            .get_job("team/project")
+            .unwrap()
+            .as_variant::<WorkflowMultiBranchProject>()
+            .unwrap();
+
+        let branches = project.get_branches(&jenkins_client).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert_eq!(
+            branches[0].branch().unwrap().branch.name.as_deref(),
+            Some("feature/one")
+        );
+        assert!(branches[0].change_request().is_none());
+        assert_eq!(
+            branches[0].metadata().unwrap().object_url.as_deref(),
+            Some("https://example.com/tree/feature/one")
+        );
+        let change_request = branches[1].change_request().unwrap();
+        assert_eq!(change_request.id.as_deref(), Some("3"));
+        assert_eq!(
+            change_request.url.as_deref(),
+            Some("https://example.com/pull/3")
+        );
+        assert_eq!(
+            branches[1].contributor().unwrap().contributor.as_deref(),
+            Some("octocat")
+        );
+
+        let branch_job = project
+            .get_branch_job(&jenkins_client, "feature/one")
+            .unwrap();
+        branch_job_mock.assert();
+        assert_eq!(branch_job.display_name, "feature/one");
+
+        project.scan(&jenkins_client).unwrap();
+        scan_mock.assert();
+        assert_eq!(
+            project.get_scan_log(&jenkins_client).unwrap(),
+            "Finished: SUCCESS"
+        );
+    }
+
+    #[test]
+    fn can_scan_organization_folder() {
+        use crate::job::{ComputedFolder, OrganizationFolder};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _get_folder_mock = mockito::mock("GET", "/job/org/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"_class": "jenkins.branch.OrganizationFolder", "name": "org",
+                    "displayName": "org", "actions": [], "url": "{url}/job/org/",
+                    "jobs": [{{"name": "repo", "url": "{url}/job/org/job/repo/"}}]}}"#,
+                url = mockito::server_url()
+            ))
+            .create();
+        let scan_mock = mockito::mock("POST", "/job/org/build")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "delay".to_string(),
+                "0".to_string(),
+            ))
+            .create();
+
+        let folder = jenkins_client
+            .get_job("org")
+            .unwrap()
+            .as_variant::<OrganizationFolder>()
+            .unwrap();
+        assert_eq!(folder.jobs[0].name, "repo");
+        folder.scan(&jenkins_client).unwrap();
+
+        scan_mock.assert();
+    }
+
+    #[test]
+    fn can_build_with_checked_parameters() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _definitions_mock = mockito::mock("GET", "/job/team/job/deploy/api/json")
+            .match_query(mockito::Matcher::Regex("tree=property".to_string()))
+            .with_body(
+                r#"{"property": [{"_class": "hudson.model.ParametersDefinitionProperty", "parameterDefinitions": [
+                    {"_class": "hudson.model.ChoiceParameterDefinition", "name": "ENV", "choices": ["staging", "production"],
+                     "defaultParameterValue": {"value": "staging"}},
+                    {"_class": "hudson.model.StringParameterDefinition", "name": "VERSION",
+                     "defaultParameterValue": {"value": "latest"}}
+                ]}]}"#,
+            )
+            .create();
+        let build_mock = mockito::mock("POST", "/job/team/job/deploy/buildWithParameters")
+            .match_body("ENV=production&VERSION=latest")
+            .with_header("Location", "http://none:8080/queue/item/1/")
+            .create();
+
+        let _ = jenkins_client
+            .job_builder("team/deploy", false)
+            .unwrap()
+            .with_parameters(&[("ENV", "production")])
+            .unwrap()
+            .send()
+            .unwrap();
+        build_mock.assert();
+
+        match jenkins_client
+            .job_builder("team/deploy", false)
+            .unwrap()
+            .with_parameters(&[("ENV", "qa")])
+            .unwrap()
+            .send()
+        {
+            Err(super::Error::InvalidParameterValue { name, message }) => {
+                assert_eq!(name, "ENV");
+                assert_eq!(message, "expected one of staging, production");
+            }
+            other => panic!("expected an invalid parameter value, got {:?}", other),
+        }
+        build_mock.assert();
+    }
+
+    #[test]
+    fn can_build_with_token_and_parameters_without_reading_job() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let definitions_mock = mockito::mock("GET", "/job/deploy/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .expect(0)
+            .create();
+        let build_mock = mockito::mock("POST", "/job/deploy/buildWithParameters")
+            .match_body("token=secret&ENV=qa")
+            .with_header("Location", "http://none:8080/queue/item/3/")
+            .create();
+
+        let _ = jenkins_client
+            .job_builder("deploy", false)
+            .unwrap()
+            .with_parameters(&[("ENV", "qa")])
+            .unwrap()
+            .remotely_with_token_and_cause("secret", None)
+            .unwrap()
+            .send()
+            .unwrap();
+        definitions_mock.assert();
+        build_mock.assert();
+    }
+
+    #[test]
+    fn can_build_with_files_and_repeated_parameters() {
+        use crate::job::FileParameter;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _definitions_mock = mockito::mock("GET", "/job/package/api/json")
+            .match_query(mockito::Matcher::Regex("tree=property".to_string()))
+            .with_body(
+                r#"{"property": [{"_class": "hudson.model.ParametersDefinitionProperty", "parameterDefinitions": [
+                    {"_class": "com.cwctravel.hudson.plugins.extended_choice_parameter.ExtendedChoiceParameterDefinition", "name": "TARGETS"},
+                    {"_class": "com.cloudbees.plugins.credentials.CredentialsParameterDefinition", "name": "CREDS", "required": true},
+                    {"_class": "hudson.model.FileParameterDefinition", "name": "archive.zip"},
+                    {"_class": "hudson.model.FileParameterDefinition", "name": "notes.txt"}
+                ]}]}"#,
+            )
+            .create();
+        let build_mock = mockito::mock("POST", "/job/package/buildWithParameters")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "delay".to_string(),
+                "0".to_string(),
+            ))
+            .match_header(
+                "content-type",
+                mockito::Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+            )
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(
+                    "name=\"TARGETS\"\r\n\r\nlinux\r\n(.|\r|\n)*name=\"TARGETS\"\r\n\r\nwindows\r\n"
+                        .to_string(),
+                ),
+                mockito::Matcher::Regex("name=\"CREDS\"\r\n\r\ndeploy-key\r\n".to_string()),
+                mockito::Matcher::Regex(
+                    "name=\"archive.zip\"; filename=\"build.zip\"\r\n(.|\r|\n)*PK-content".to_string(),
+                ),
+                mockito::Matcher::Regex(
+                    "name=\"notes.txt\"; filename=\"jenkins_api_notes.txt\"\r\n(.|\r|\n)*release notes"
+                        .to_string(),
+                ),
+            ]))
+            .with_header("Location", "http://none:8080/queue/item/2/")
+            .create();
+
+        let notes = std::env::temp_dir().join("jenkins_api_notes.txt");
+        std::fs::write(&notes, "release notes").unwrap();
+
+        let _ = jenkins_client
+            .job_builder("package", false)
+            .unwrap()
+            .with_delay(0)
+            .with_parameters(&[("TARGETS", "linux"), ("TARGETS", "windows")])
+            .unwrap()
+            .with_parameter("CREDS", "deploy-key")
+            .unwrap()
+            .with_file_parameter(
+                "archive.zip",
+                FileParameter::from_bytes("build.zip", &b"PK-content"[..]),
+            )
+            .unwrap()
+            .with_file_parameter("notes.txt", FileParameter::from_path(&notes).unwrap())
+            .unwrap()
+            .send()
+            .unwrap();
+
+        build_mock.assert();
+        std::fs::remove_file(&notes).unwrap();
+
+        match FileParameter::from_path(std::env::temp_dir().join("jenkins_api_missing.txt")) {
+            Err(super::Error::Io { .. }) => (),
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn can_wait_for_build_to_start_and_complete() {
+        use crate::build::BuildStatus;
+        use std::time::Duration;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _build_mock = mockito::mock("POST", "/job/wait/build")
+            .with_header(
+                "Location",
+                &format!("{}/queue/item/41/", mockito::server_url()),
+            )
+            .create();
+        let _queue_item_mock = mockito::mock("GET", "/queue/item/41/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(format!(
+                r#"{{"cancelled": false, "executable": {{"number": 7, "url": "{}/job/wait/7/"}}}}"#,
+                mockito::server_url()
+            ))
+            .create();
+        let _build_state_mock = mockito::mock("GET", "/job/wait/7/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"building": false, "result": "UNSTABLE"}"#)
+            .create();
+
+        let build = jenkins_client
+            .job_builder("wait", false)
+            .unwrap()
+            .send_and_wait_started(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(build.number, 7);
+        assert_eq!(
+            build
+                .wait_for_completion(
+                    &jenkins_client,
+                    Duration::from_millis(10),
+                    Duration::from_secs(5)
+                )
+                .unwrap(),
+            BuildStatus::Unstable
+        );
+    }
+
+    #[test]
+    fn can_find_build_of_expired_queue_item() {
+        use std::time::Duration;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _build_mock = mockito::mock("POST", "/job/team/job/expired/build")
+            .with_header(
+                "Location",
+                &format!("{}/queue/item/42/", mockito::server_url()),
+            )
+            .create();
+        let _queue_item_mock = mockito::mock("GET", "/queue/item/42/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create();
+        let _builds_mock = mockito::mock("GET", "/job/team/job/expired/api/json")
+            .match_query(mockito::Matcher::Regex("tree=builds".to_string()))
+            .with_body(format!(
+                r#"{{"builds": [
+                    {{"number": 3, "queueId": 43, "url": "{url}/job/team/job/expired/3/"}},
+                    {{"number": 2, "queueId": 42, "url": "{url}/job/team/job/expired/2/"}}
+                ]}}"#,
+                url = mockito::server_url()
+            ))
+            .create();
+
+        let build = jenkins_client
+            .job_builder("team/expired", false)
+            .unwrap()
+            .send_and_wait_started(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(build.number, 2);
+    }
+
+    #[test]
+    fn can_get_error_waiting_for_cancelled_or_long_builds() {
+        use crate::build::{BuildStatus, ShortBuild};
+        use std::time::Duration;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _build_mock = mockito::mock("POST", "/job/cancelled/build")
+            .with_header(
+                "Location",
+                &format!("{}/queue/item/44/", mockito::server_url()),
+            )
+            .create();
+        let _queue_item_mock = mockito::mock("GET", "/queue/item/44/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"cancelled": true, "executable": null}"#)
+            .create();
+        let _build_state_mock = mockito::mock("GET", "/job/long/1/api/json")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"building": true, "result": null}"#)
+            .create();
+
+        match jenkins_client
+            .job_builder("cancelled", false)
+            .unwrap()
+            .send_and_wait_started(Duration::from_secs(5))
+        {
+            Err(super::Error::QueueItemCancelled { url }) => {
+                assert!(url.ends_with("/queue/item/44/"))
+            }
+            other => panic!("expected a cancelled queue item, got {:?}", other),
+        }
+
+        let build: ShortBuild = serde_json::from_str(&format!(
+            r#"{{"number": 1, "url": "{}/job/long/1/"}}"#,
+            mockito::server_url()
+        ))
+        .unwrap();
+        let result: super::Result<BuildStatus> = build.wait_for_completion(
+            &jenkins_client,
+            Duration::from_millis(10),
+            Duration::from_millis(30),
+        );
+        match result {
+            Err(super::Error::Timeout { timeout, .. }) => {
+                assert_eq!(timeout, Duration::from_millis(30))
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn can_stream_console_of_matrix_configuration_in_folder() {
+        use crate::build::{Build, CommonBuild};
+        use std::io::Read;
+        use std::time::Duration;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build: CommonBuild = serde_json::from_str(&format!(
+            r##"{{"url": "{}/job/team/job/matrix/os=linux/3/", "number": 3, "duration": 0,
+                "estimatedDuration": 0, "timestamp": 0, "keepLog": false, "result": null,
+                "displayName": "#3", "building": true, "id": "3", "queueId": 1,
+                "actions": [], "artifacts": []}}"##,
+            mockito::server_url()
+        ))
+        .unwrap();
+
+        let log_path = "/job/team/job/matrix/os=linux/3/logText/progressiveText";
+        let _first_mock = mockito::mock("GET", log_path)
+            .match_query(mockito::Matcher::UrlEncoded(
+                "start".to_string(),
+                "0".to_string(),
+            ))
+            .with_header("X-Text-Size", "7")
+            .with_header("X-More-Data", "true")
+            .with_body("line 1\n")
+            .create();
+        let _running_mock = mockito::mock("GET", log_path)
+            .match_query(mockito::Matcher::UrlEncoded(
+                "start".to_string(),
+                "7".to_string(),
+            ))
+            .with_header("X-Text-Size", "14")
+            .with_body("line 2\n")
+            .create();
+
+        let chunks = build
+            .stream_console(&jenkins_client)
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10))
+            .collect::<super::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks, vec![b"line 1\n".to_vec(), b"line 2\n".to_vec()]);
+
+        let mut console = String::new();
+        let _ = build
+            .stream_console(&jenkins_client)
+            .unwrap()
+            .read_to_string(&mut console)
+            .unwrap();
+        assert_eq!(console, "line 1\nline 2\n");
+
+        let mut resumed = build.stream_console_from(&jenkins_client, 7).unwrap();
+        assert_eq!(resumed.next().unwrap().unwrap(), b"line 2\n".to_vec());
+        assert!(resumed.next().is_none());
+        assert_eq!(resumed.offset(), 14);
+    }
+
+    #[test]
+    fn can_act_on_builds_in_folders_and_matrix_runs() {
+        use crate::build::{Build, CommonBuild};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build_json = |path: &str, keep_log: bool, display_name: &str, description: &str| {
+            format!(
+                r#"{{"url": "{}{}", "number": 5, "duration": 0, "estimatedDuration": 0,
+                    "timestamp": 0, "keepLog": {}, "result": null, "displayName": "{}",
+                    "description": {}, "building": true, "id": "5", "queueId": 1,
+                    "actions": [], "artifacts": []}}"#,
+                mockito::server_url(),
+                path,
+                keep_log,
+                display_name,
+                description
+            )
+        };
+        let build: CommonBuild = serde_json::from_str(&build_json(
+            "/job/team/job/pipeline/5/",
+            false,
+            "#5",
+            "null",
+        ))
+        .unwrap();
+        let run: CommonBuild = serde_json::from_str(&build_json(
+            "/job/team/job/matrix/os=linux/5/",
+            false,
+            "#5",
+            "null",
+        ))
+        .unwrap();
+
+        let action_mocks = ["stop", "term", "kill", "toggleLogKeep", "doDelete"]
+            .iter()
+            .map(|action| {
+                mockito::mock(
+                    "POST",
+                    format!("/job/team/job/pipeline/5/{}", action).as_str(),
+                )
+                .create()
+            })
+            .collect::<Vec<_>>();
+        let run_stop_mock = mockito::mock("POST", "/job/team/job/matrix/os=linux/5/stop").create();
+        let description_mock = mockito::mock("POST", "/job/team/job/pipeline/5/submitDescription")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "description".to_string(),
+                "release candidate".to_string(),
+            ))
+            .create();
+        let _current_description_mock = mockito::mock("GET", "/job/team/job/pipeline/5/api/json")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "tree".to_string(),
+                "description".to_string(),
+            ))
+            .with_body(r#"{"description": "release candidate"}"#)
+            .create();
+        let display_name_mock = mockito::mock("POST", "/job/team/job/pipeline/5/configSubmit")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "json".to_string(),
+                r#"{"description":"release candidate","displayName":"v1.0"}"#.to_string(),
+            ))
+            .create();
+        let _refresh_mock = mockito::mock("GET", "/job/team/job/pipeline/5/api/json?depth=1")
+            .with_body(build_json(
+                "/job/team/job/pipeline/5/",
+                true,
+                "v1.0",
+                r#""release candidate""#,
+            ))
+            .create();
+
+        build.stop(&jenkins_client).unwrap();
+        build.term(&jenkins_client).unwrap();
+        build.kill(&jenkins_client).unwrap();
+        run.stop(&jenkins_client).unwrap();
+        build.toggle_keep_log(&jenkins_client).unwrap();
+        build
+            .set_description(&jenkins_client, "release candidate")
+            .unwrap();
+        build.set_display_name(&jenkins_client, "v1.0").unwrap();
+
+        let refreshed: CommonBuild = jenkins_client.get_build("team/pipeline", 5).unwrap();
+        assert!(refreshed.keep_log);
+        assert_eq!(refreshed.display_name, "v1.0");
+        assert_eq!(refreshed.description.as_deref(), Some("release candidate"));
+
+        refreshed.delete(&jenkins_client).unwrap();
+
+        for mock in action_mocks {
+            mock.assert();
+        }
+        run_stop_mock.assert();
+        description_mock.assert();
+        display_name_mock.assert();
+    }
+
+    #[test]
+    fn can_download_and_resume_artifacts_with_checksums() {
+        use crate::build::{Build, CommonBuild};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build_json = |number: u32| {
+            format!(
+                r#"{{"url": "{}/job/team/job/app/{}/", "number": {}, "duration": 0,
+                    "estimatedDuration": 0, "timestamp": 0, "keepLog": false, "result": "SUCCESS",
+                    "displayName": "", "building": false, "id": "", "queueId": 1, "actions": [],
+                    "artifacts": [{{"fileName": "app.tar.gz", "relativePath": "dist/app.tar.gz"}}]}}"#,
+                mockito::server_url(),
+                number,
+                number
+            )
+        };
+        let build: CommonBuild = serde_json::from_str(&build_json(7)).unwrap();
+        let corrupted_build: CommonBuild = serde_json::from_str(&build_json(8)).unwrap();
+
+        let _artifacts_mock = mockito::mock("GET", "/job/team/job/app/7/api/json")
+            .match_query(mockito::Matcher::Regex("tree=".to_string()))
+            .with_body(
+                r#"{"_class": "hudson.model.FreeStyleBuild",
+                    "artifacts": [
+                        {"fileName": "app.tar.gz", "relativePath": "dist/app.tar.gz"},
+                        {"fileName": "read me.txt", "relativePath": "docs/read me.txt"}
+                    ],
+                    "fingerprint": [
+                        {"fileName": "dist/app.tar.gz", "hash": "58e7cb81e178fb5b5c2398c4518bd1b7"},
+                        {"fileName": "docs/read me.txt", "hash": "5eb63bbbe01eeed093cb22bb8f5acdc3"}
+                    ]}"#,
+            )
+            .create();
+        let app_mock = mockito::mock("GET", "/job/team/job/app/7/artifact/dist/app.tar.gz")
+            .with_body("app content")
+            .expect(2)
+            .create();
+        let resume_mock = mockito::mock("GET", "/job/team/job/app/7/artifact/docs/read%20me.txt")
+            .match_header("range", "bytes=6-")
+            .with_status(206)
+            .with_body("world")
+            .create();
+        let complete_mock = mockito::mock("GET", "/job/team/job/app/7/artifact/docs/read%20me.txt")
+            .match_header("range", "bytes=11-")
+            .with_status(416)
+            .create();
+        let readme_mock = mockito::mock("GET", "/job/team/job/app/7/artifact/docs/read%20me.txt")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_body("hello world")
+            .create();
+        let _corrupted_artifacts_mock = mockito::mock("GET", "/job/team/job/app/8/api/json")
+            .match_query(mockito::Matcher::Regex("tree=".to_string()))
+            .with_body(
+                r#"{"artifacts": [{"fileName": "app.tar.gz", "relativePath": "dist/app.tar.gz"}],
+                    "fingerprint": [{"fileName": "app.tar.gz", "hash": "58e7cb81e178fb5b5c2398c4518bd1b7"}]}"#,
+            )
+            .create();
+        let _corrupted_mock = mockito::mock("GET", "/job/team/job/app/8/artifact/dist/app.tar.gz")
+            .with_body("corrupted")
+            .create();
+
+        let mut content = vec![];
+        let size = build
+            .download_artifact(&jenkins_client, &build.artifacts[0], &mut content)
+            .unwrap();
+        assert_eq!(size, 11);
+        assert_eq!(content, b"app content");
+
+        let dir = std::env::temp_dir().join("jenkins_api_artifacts");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::write(dir.join("docs").join("read me.txt"), "hello ").unwrap();
+
+        let files = build.download_all_artifacts(&jenkins_client, &dir).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.join("dist/app.tar.gz"), dir.join("docs/read me.txt")]
+        );
+        assert_eq!(std::fs::read_to_string(&files[0]).unwrap(), "app content");
+        assert_eq!(std::fs::read_to_string(&files[1]).unwrap(), "hello world");
+
+        let _ = build.download_all_artifacts(&jenkins_client, &dir).unwrap();
+        assert_eq!(std::fs::read_to_string(&files[1]).unwrap(), "hello world");
+
+        // A complete file not matching its checksum is downloaded again
+        std::fs::write(&files[1], "hello wOrld").unwrap();
+        let _ = build.download_all_artifacts(&jenkins_client, &dir).unwrap();
+        assert_eq!(std::fs::read_to_string(&files[1]).unwrap(), "hello world");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        match corrupted_build.download_all_artifacts(&jenkins_client, &dir) {
+            Err(super::Error::ArtifactChecksumMismatch {
+                expected, actual, ..
+            }) => {
+                assert_eq!(expected, "58e7cb81e178fb5b5c2398c4518bd1b7");
+                assert_ne!(actual, expected);
+            }
+            other => panic!("expected a checksum mismatch, got {:?}", other),
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        app_mock.assert();
+        resume_mock.assert();
+        complete_mock.assert();
+        readme_mock.assert();
+    }
+
+    #[test]
+    fn can_download_again_artifacts_without_checksums() {
+        use crate::build::{Build, CommonBuild};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build: CommonBuild = serde_json::from_str(&format!(
+            r#"{{"url": "{}/job/libs/3/", "number": 3, "duration": 0, "estimatedDuration": 0,
+                "timestamp": 0, "keepLog": false, "result": "SUCCESS", "displayName": "",
+                "building": false, "id": "", "queueId": 1, "actions": [], "artifacts": []}}"#,
+            mockito::server_url()
+        ))
+        .unwrap();
+
+        let _artifacts_mock = mockito::mock("GET", "/job/libs/3/api/json")
+            .match_query(mockito::Matcher::Regex("tree=".to_string()))
+            .with_body(
+                r#"{"_class": "hudson.model.FreeStyleBuild",
+                    "artifacts": [
+                        {"fileName": "notes.txt", "relativePath": "notes.txt"},
+                        {"fileName": "lib.jar", "relativePath": "a/lib.jar"},
+                        {"fileName": "lib.jar", "relativePath": "b/lib.jar"}
+                    ],
+                    "fingerprint": [
+                        {"fileName": "lib.jar", "hash": "00000000000000000000000000000000"}
+                    ]}"#,
+            )
+            .create();
+        let notes_mock = mockito::mock("GET", "/job/libs/3/artifact/notes.txt")
+            .match_header("range", mockito::Matcher::Missing)
+            .with_body("fresh notes")
+            .create();
+        let jar_mocks: Vec<_> = ["a", "b"]
+            .iter()
+            .map(|dir| {
+                mockito::mock(
+                    "GET",
+                    format!("/job/libs/3/artifact/{}/lib.jar", dir).as_str(),
+                )
+                .match_header("range", mockito::Matcher::Missing)
+                .with_body("jar")
+                .create()
+            })
+            .collect();
+
+        let dir = std::env::temp_dir().join("jenkins_api_stale_artifacts");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "stale notes, and longer").unwrap();
+
+        // The fingerprint of `lib.jar` could be the one of either artifact, so it isn't checked
+        let files = build.download_all_artifacts(&jenkins_client, &dir).unwrap();
+        assert_eq!(std::fs::read_to_string(&files[0]).unwrap(), "fresh notes");
+        assert_eq!(std::fs::read_to_string(&files[1]).unwrap(), "jar");
+        assert_eq!(std::fs::read_to_string(&files[2]).unwrap(), "jar");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        notes_mock.assert();
+        for mock in jar_mocks {
+            mock.assert();
+        }
+    }
+
+    #[test]
+    fn can_reject_artifacts_outside_of_the_download_directory() {
+        use crate::build::{Build, CommonBuild};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build: CommonBuild = serde_json::from_str(&format!(
+            r#"{{"url": "{}/job/evil/1/", "number": 1, "duration": 0, "estimatedDuration": 0,
+                "timestamp": 0, "keepLog": false, "result": "SUCCESS", "displayName": "",
+                "building": false, "id": "", "queueId": 1, "actions": [], "artifacts": []}}"#,
+            mockito::server_url()
+        ))
+        .unwrap();
+
+        let dir = std::env::temp_dir().join("jenkins_api_evil_artifacts");
+        for relative_path in &[
+            "../escaped.txt",
+            "logs/../../escaped.txt",
+            "/etc/escaped.txt",
+        ] {
+            let _artifacts_mock = mockito::mock("GET", "/job/evil/1/api/json")
+                .match_query(mockito::Matcher::Regex("tree=".to_string()))
+                .with_body(format!(
+                    r#"{{"artifacts": [{{"fileName": "escaped.txt", "relativePath": "{}"}}]}}"#,
+                    relative_path
+                ))
+                .create();
+            let download_mock =
+                mockito::mock("GET", mockito::Matcher::Regex("/artifact/".to_string()))
+                    .expect(0)
+                    .create();
+
+            match build.download_all_artifacts(&jenkins_client, &dir) {
+                Err(super::Error::InvalidArtifactPath { path }) => assert_eq!(&path, relative_path),
+                other => panic!("expected an invalid artifact path, got {:?}", other),
+            }
+            download_mock.assert();
+        }
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn can_get_plain_aggregated_and_sliced_test_reports() {
+        use crate::build::{Build, CommonBuild, TestStatus};
+        use crate::client::{AdvancedQuery, TreeBuilder};
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let build_json = |path: &str| {
+            format!(
+                r#"{{"url": "{}{}", "number": 4, "duration": 0, "estimatedDuration": 0,
+                    "timestamp": 0, "keepLog": false, "result": "UNSTABLE", "displayName": "",
+                    "building": false, "id": "4", "queueId": 1, "actions": [], "artifacts": []}}"#,
+                mockito::server_url(),
+                path
+            )
+        };
+        let build: CommonBuild =
+            serde_json::from_str(&build_json("/job/team/job/pipeline/4/")).unwrap();
+        let matrix_build: CommonBuild =
+            serde_json::from_str(&build_json("/job/matrix/4/")).unwrap();
+
+        let report = r#"{"_class": "hudson.tasks.junit.TestResult", "duration": 1.5,
+            "failCount": 1, "passCount": 1, "skipCount": 0, "suites": [
+                {"name": "app.LoginTest", "duration": 1.5, "timestamp": "2020-01-01T00:00:00",
+                 "stdout": null, "stderr": null, "cases": [
+                    {"className": "app.LoginTest", "name": "logs in", "duration": 0.5,
+                     "status": "PASSED", "age": 0, "failedSince": 0, "skipped": false},
+                    {"className": "app.LoginTest", "name": "rejects bad password",
+                     "duration": 1.0, "status": "REGRESSION", "age": 1, "failedSince": 4,
+                     "skipped": false, "errorDetails": "expected 401", "errorStackTrace": "at LoginTest",
+                     "stdout": "logging in", "stderr": null}
+                ]}
+            ]}"#;
+        let _report_mock = mockito::mock("GET", "/job/team/job/pipeline/4/testReport/api/json")
+            .match_query(mockito::Matcher::Missing)
+            .with_body(report)
+            .create();
+        let _matrix_report_mock = mockito::mock("GET", "/job/matrix/4/testReport/api/json")
+            .with_body(format!(
+                r#"{{"_class": "hudson.matrix.MatrixTestResult", "failCount": 1, "skipCount": 0,
+                    "totalCount": 2, "childReports": [
+                        {{"child": {{"number": 4, "url": "{}/job/matrix/os=linux/4/"}},
+                          "result": {}}}
+                    ]}}"#,
+                mockito::server_url(),
+                report
+            ))
+            .create();
+        let _sliced_report_mock =
+            mockito::mock("GET", "/job/team/job/pipeline/4/testReport/api/json")
+                .match_query(mockito::Matcher::UrlEncoded(
+                    "tree".to_string(),
+                    "suites[name,cases[name,status]{,1}]".to_string(),
+                ))
+                .with_body(
+                    r#"{"suites": [{"name": "app.LoginTest",
+                        "cases": [{"name": "logs in", "status": "PASSED"}]}]}"#,
+                )
+                .create();
+
+        let test_report = build.get_test_report(&jenkins_client).unwrap();
+        assert_eq!(test_report.fail_count, 1);
+        assert_eq!(test_report.pass_count, Some(1));
+        let suites = test_report.all_suites();
+        assert_eq!(suites.len(), 1);
+        assert_eq!(suites[0].timestamp.as_deref(), Some("2020-01-01T00:00:00"));
+        let failing = &test_report.all_cases()[1];
+        assert_eq!(failing.class_name, "app.LoginTest");
+        assert_eq!(failing.status, Some(TestStatus::Regression));
+        assert!(failing.status.unwrap().is_failure());
+        assert_eq!(failing.age, 1);
+        assert_eq!(failing.error_details.as_deref(), Some("expected 401"));
+        assert_eq!(failing.stdout.as_deref(), Some("logging in"));
+
+        let matrix_report = matrix_build.get_test_report(&jenkins_client).unwrap();
+        assert_eq!(matrix_report.total_count, Some(2));
+        assert_eq!(matrix_report.suites.len(), 0);
+        assert_eq!(matrix_report.all_cases().len(), 2);
+        assert_eq!(
+            matrix_report.child_reports[0]
+                .child
+                .as_ref()
+                .unwrap()
+                .number,
+            4
+        );
+
+        let sliced = build
+            .get_test_report_with(
+                &jenkins_client,
+                AdvancedQuery::Tree(
+                    TreeBuilder::object("suites")
+                        .with_subfield("name")
+                        .with_subfield(
+                            TreeBuilder::object("cases")
+                                .with_subfield("name")
+                                .with_subfield("status")
+                                .with_range(..1),
+                        )
+                        .build(),
+                ),
+            )
+            .unwrap();
+        let cases = sliced.all_cases();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].name, "logs in");
+        assert_eq!(cases[0].class_name, "");
+    }
+
+    #[test]
+    fn can_compare_test_reports_of_build_aliases() {
+        use crate::build::BuildNumber;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _base_mock = mockito::mock(
+            "GET",
+            "/job/team/job/app/lastSuccessfulBuild/testReport/api/json?depth=1",
+        )
+        .with_body(
+            r#"{"suites": [{"name": "app.Test", "cases": [
+                {"className": "app.Test", "name": "old", "status": "PASSED"},
+                {"className": "app.Test", "name": "flaky", "status": "PASSED"}
+            ]}]}"#,
+        )
+        .create();
+        let _compared_mock =
+            mockito::mock("GET", "/job/team/job/app/lastBuild/testReport/api/json?depth=1")
+                .with_body(
+                    r#"{"suites": [{"name": "app.Test", "cases": [
+                        {"className": "app.Test", "name": "flaky", "status": "REGRESSION", "age": 1},
+                        {"className": "app.Test", "name": "new", "status": "PASSED"}
+                    ]}]}"#,
+                )
+                .create();
+
+        let comparison = jenkins_client
+            .compare_test_reports(
+                "team/app",
+                BuildNumber::LastSuccessfulBuild,
+                BuildNumber::LastBuild,
+            )
+            .unwrap();
+
+        assert_eq!(comparison.newly_failing.len(), 1);
+        assert_eq!(comparison.newly_failing[0].name, "flaky");
+        assert_eq!(comparison.newly_failing[0].age, 1);
+        assert_eq!(comparison.added[0].name, "new");
+        assert_eq!(comparison.removed[0].name, "old");
+        assert!(comparison.has_regressions());
+    }
+
+    #[test]
+    fn can_get_build_history_of_job_in_folder() {
+        use crate::build::BuildStatus;
+        use crate::job::Job;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _get_job_mock = mockito::mock("GET", "/job/team/job/app/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"name": "app", "displayName": "app", "actions": [], "url": "{}/job/team/job/app/"}}"#,
+                mockito::server_url()
+            ))
+            .create();
+        let history_mock = mockito::mock("GET", "/job/team/job/app/api/json")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "tree".to_string(),
+                "allBuilds[number,result,duration,timestamp,building]{0,3}".to_string(),
+            ))
+            .with_body(
+                r#"{"_class": "hudson.model.FreeStyleProject", "allBuilds": [
+                    {"number": 3, "result": null, "duration": 0, "timestamp": 3000, "building": true},
+                    {"number": 2, "result": "SUCCESS", "duration": 500, "timestamp": 2000, "building": false},
+                    {"number": 1, "result": "FAILURE", "duration": 100, "timestamp": 1000, "building": false}
+                ]}"#,
+            )
+            .create();
+
+        let job = jenkins_client.get_job("team/app").unwrap();
+        let history = job.build_history(&jenkins_client, 3).unwrap();
+
+        assert_eq!(history.builds.len(), 3);
+        assert_eq!(history.builds[1].result, Some(BuildStatus::Success));
+        let statistics = history.statistics();
+        assert_eq!(statistics.builds, 2);
+        assert_eq!(statistics.success_rate, 0.5);
+        assert_eq!(
+            statistics.mean_time_to_recovery,
+            Some(std::time::Duration::from_millis(1500))
+        );
+        history_mock.assert();
+    }
+
+    #[test]
+    fn can_get_pipeline_stages_steps_and_logs() {
+        use crate::build::{StageStatus, WorkflowRun};
+        use crate::job::WorkflowJob;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let job: WorkflowJob = serde_json::from_str(&format!(
+            r#"{{"name": "deploy", "displayName": "deploy", "url": "{}/job/team/job/deploy/",
+                "actions": [], "buildable": true, "keepDependencies": false,
+                "nextBuildNumber": 8, "inQueue": false, "builds": [], "healthReport": [],
+                "property": [], "description": "", "concurrentBuild": false}}"#,
+            mockito::server_url()
+        ))
+        .unwrap();
+        let run: WorkflowRun = serde_json::from_str(&format!(
+            r#"{{"url": "{}/job/team/job/deploy/7/", "number": 7, "duration": 0,
+                "estimatedDuration": 0, "timestamp": 0, "keepLog": false, "result": "FAILURE",
+                "displayName": "", "building": false, "id": "7", "queueId": 1, "actions": [],
+                "artifacts": [], "changeSets": [], "previousBuild": null}}"#,
+            mockito::server_url()
+        ))
+        .unwrap();
+
+        let run_description = r#"{"id": "7", "name": "Build 7", "status": "FAILED",
+            "startTimeMillis": 1000, "endTimeMillis": 9000, "durationMillis": 8000,
+            "queueDurationMillis": 5, "pauseDurationMillis": 0, "stages": [
+                {"id": "6", "name": "Build", "execNode": "", "status": "SUCCESS",
+                 "startTimeMillis": 1000, "durationMillis": 3000, "pauseDurationMillis": 0},
+                {"id": "12", "name": "Test", "execNode": "", "status": "FAILED",
+                 "startTimeMillis": 4000, "durationMillis": 5000, "pauseDurationMillis": 0,
+                 "error": {"message": "script returned exit code 1",
+                           "type": "hudson.AbortException"}},
+                {"id": "20", "name": "Deploy", "execNode": "", "status": "NOT_EXECUTED",
+                 "startTimeMillis": 0, "durationMillis": 0, "pauseDurationMillis": 0}
+            ]}"#;
+        let _runs_mock = mockito::mock("GET", "/job/team/job/deploy/wfapi/runs")
+            .with_body(format!("[{}]", run_description))
+            .create();
+        let _describe_mock = mockito::mock("GET", "/job/team/job/deploy/7/wfapi/describe")
+            .with_body(run_description)
+            .create();
+        let _stage_mock = mockito::mock(
+            "GET",
+            "/job/team/job/deploy/7/execution/node/12/wfapi/describe",
+        )
+        .with_body(
+            r#"{"id": "12", "name": "Test", "execNode": "", "status": "FAILED",
+                "startTimeMillis": 4000, "durationMillis": 5000, "pauseDurationMillis": 0,
+                "stageFlowNodes": [
+                    {"id": "13", "name": "Shell Script", "execNode": "", "status": "FAILED",
+                     "parameterDescription": "make test", "startTimeMillis": 4100,
+                     "durationMillis": 4800, "pauseDurationMillis": 0, "parentNodes": ["12"],
+                     "error": {"message": "script returned exit code 1"}}
+                ]}"#,
+        )
+        .create();
+        let _log_mock = mockito::mock("GET", "/job/team/job/deploy/7/execution/node/13/wfapi/log")
+            .with_body(
+                r#"{"nodeId": "13", "nodeStatus": "FAILED", "length": 12, "hasMore": false,
+                    "text": "test failed\n", "consoleUrl": "/job/team/job/deploy/7/execution/node/13/log"}"#,
+            )
+            .create();
+
+        let runs = job.get_runs(&jenkins_client).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, StageStatus::Failed);
+
+        let description = run.describe(&jenkins_client).unwrap();
+        assert_eq!(description.stages.len(), 3);
+        assert_eq!(description.stages[2].status, StageStatus::NotExecuted);
+        let failed_stage = description.failed_stage().unwrap();
+        assert_eq!(failed_stage.name, "Test");
+        assert_eq!(failed_stage.duration_millis, 5000);
+        assert_eq!(
+            failed_stage.error.as_ref().unwrap().message,
+            "script returned exit code 1"
+        );
+
+        let stage = run.get_stage(&jenkins_client, &failed_stage.id).unwrap();
+        assert_eq!(stage.steps.len(), 1);
+        assert_eq!(
+            stage.steps[0].parameter_description.as_deref(),
+            Some("make test")
+        );
+
+        let log = run
+            .get_node_log(&jenkins_client, &stage.steps[0].id)
+            .unwrap();
+        assert_eq!(log.node_status, StageStatus::Failed);
+        assert_eq!(log.text, "test failed\n");
+        assert!(!log.has_more);
+    }
 }
@@ -1,4 +1,6 @@
-use super::Jenkins;
+use std::fmt;
+
+use super::{Error, Jenkins, Result};
 use crate::build;
 
 /// Name of an object
@@ -10,12 +12,38 @@ pub enum Name<'a> {
     UrlEncodedName(&'a str),
 }
 
-impl<'a> ToString for Name<'a> {
-    fn to_string(&self) -> String {
-        match *self {
+impl<'a> fmt::Display for Name<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&match *self {
             Name::Name(name) => urlencoding::encode(name),
             Name::UrlEncodedName(name) => name.to_string(),
-        }
+        })
+    }
+}
+
+/// Action posted to a build
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum BuildAction {
+    Stop,
+    Term,
+    Kill,
+    Delete,
+    ToggleKeepLog,
+    SubmitDescription,
+    ConfigSubmit,
+}
+
+impl fmt::Display for BuildAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            BuildAction::Stop => "stop",
+            BuildAction::Term => "term",
+            BuildAction::Kill => "kill",
+            BuildAction::Delete => "doDelete",
+            BuildAction::ToggleKeepLog => "toggleLogKeep",
+            BuildAction::SubmitDescription => "submitDescription",
+            BuildAction::ConfigSubmit => "configSubmit",
+        })
     }
 }
 
@@ -25,6 +53,10 @@ pub enum Path<'a> {
     View {
         name: Name<'a>,
     },
+    InView {
+        view_name: Name<'a>,
+        path: Box<Path<'a>>,
+    },
     AddJobToView {
         job_name: Name<'a>,
         view_name: Name<'a>,
@@ -52,6 +84,19 @@ pub enum Path<'a> {
     JobDisable {
         name: Name<'a>,
     },
+    JobRename {
+        name: Name<'a>,
+    },
+    JobDelete {
+        name: Name<'a>,
+    },
+    JobMove {
+        name: Name<'a>,
+    },
+    ComputationLog {
+        job_name: Name<'a>,
+    },
+    CreateItem,
     Build {
         job_name: Name<'a>,
         number: build::BuildNumber,
@@ -61,11 +106,48 @@ pub enum Path<'a> {
         job_name: Name<'a>,
         number: build::BuildNumber,
         configuration: Option<Name<'a>>,
-        folder_name: Option<Name<'a>>,
+    },
+    ProgressiveText {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
+    BuildAction {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+        action: BuildAction,
+    },
+    WorkflowRuns {
+        job_name: Name<'a>,
+    },
+    WorkflowRunDescribe {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+    },
+    WorkflowNodeDescribe {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        node_id: Name<'a>,
+    },
+    WorkflowNodeLog {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        node_id: Name<'a>,
+    },
+    TestReport {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+    },
+    Artifact {
+        job_name: Name<'a>,
+        number: build::BuildNumber,
+        configuration: Option<Name<'a>>,
+        relative_path: &'a str,
     },
     ConfigXML {
         job_name: Name<'a>,
-        folder_name: Option<Name<'a>>,
     },
     Queue,
     QueueItem {
@@ -90,232 +172,442 @@ pub enum Path<'a> {
     CrumbIssuer,
 }
 
-impl<'a> ToString for Path<'a> {
-    fn to_string(&self) -> String {
-        match *self {
+impl<'a> fmt::Display for Path<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&match *self {
             Path::Home => "".to_string(),
-            Path::View { ref name } => format!("/view/{}", name.to_string()),
+            Path::View { ref name } => format!("/view/{}", name),
+            Path::InView {
+                ref view_name,
+                ref path,
+            } => format!("/view/{}{}", view_name, path),
             Path::AddJobToView {
                 ref job_name,
                 ref view_name,
-            } => format!(
-                "/view/{}/addJobToView?name={}",
-                view_name.to_string(),
-                job_name.to_string()
-            ),
+            } => format!("/view/{}/addJobToView?name={}", view_name, job_name),
             Path::RemoveJobFromView {
                 ref job_name,
                 ref view_name,
-            } => format!(
-                "/view/{}/removeJobFromView?name={}",
-                view_name.to_string(),
-                job_name.to_string()
-            ),
+            } => format!("/view/{}/removeJobFromView?name={}", view_name, job_name),
             Path::Job {
                 ref name,
                 configuration: Some(ref configuration),
-            } => format!("/job/{}/{}", name.to_string(), configuration.to_string()),
+            } => format!("/job/{}/{}", name, configuration),
             Path::Job {
                 ref name,
                 configuration: None,
-            } => format!("/job/{}", name.to_string()),
-            Path::BuildJob { ref name } => format!("/job/{}/build", name.to_string()),
+            } => format!("/job/{}", name),
+            Path::BuildJob { ref name } => format!("/job/{}/build", name),
             Path::BuildJobWithParameters { ref name } => {
-                format!("/job/{}/buildWithParameters", name.to_string())
+                format!("/job/{}/buildWithParameters", name)
+            }
+            Path::PollSCMJob { ref name } => format!("/job/{}/polling", name),
+            Path::JobEnable { ref name } => format!("/job/{}/enable", name),
+            Path::JobDisable { ref name } => format!("/job/{}/disable", name),
+            Path::JobRename { ref name } => format!("/job/{}/doRename", name),
+            Path::JobDelete { ref name } => format!("/job/{}/doDelete", name),
+            Path::JobMove { ref name } => format!("/job/{}/move/move", name),
+            Path::ComputationLog { ref job_name } => {
+                format!("/job/{}/computation/consoleText", job_name)
             }
-            Path::PollSCMJob { ref name } => format!("/job/{}/polling", name.to_string()),
-            Path::JobEnable { ref name } => format!("/job/{}/enable", name.to_string()),
-            Path::JobDisable { ref name } => format!("/job/{}/disable", name.to_string()),
+            Path::CreateItem => "/createItem".to_string(),
             Path::Build {
                 ref job_name,
                 ref number,
                 configuration: None,
-            } => format!("/job/{}/{}", job_name.to_string(), number.to_string()),
+            } => format!("/job/{}/{}", job_name, number),
             Path::Build {
                 ref job_name,
                 ref number,
                 configuration: Some(ref configuration),
-            } => format!(
-                "/job/{}/{}/{}",
-                job_name.to_string(),
-                configuration.to_string(),
-                number.to_string()
-            ),
+            } => format!("/job/{}/{}/{}", job_name, configuration, number),
             Path::ConsoleText {
                 ref job_name,
                 ref number,
                 configuration: None,
-                folder_name: None,
-            } => format!(
-                "/job/{}/{}/consoleText",
-                job_name.to_string(),
-                number.to_string()
-            ),
+            } => format!("/job/{}/{}/consoleText", job_name, number),
             Path::ConsoleText {
                 ref job_name,
                 ref number,
                 configuration: Some(ref configuration),
-                folder_name: None,
+            } => format!("/job/{}/{}/{}/consoleText", job_name, configuration, number),
+            Path::ProgressiveText {
+                ref job_name,
+                ref number,
+                configuration: None,
+            } => format!("/job/{}/{}/logText/progressiveText", job_name, number),
+            Path::ProgressiveText {
+                ref job_name,
+                ref number,
+                configuration: Some(ref configuration),
             } => format!(
-                "/job/{}/{}/{}/consoleText",
-                job_name.to_string(),
-                configuration.to_string(),
-                number.to_string()
+                "/job/{}/{}/{}/logText/progressiveText",
+                job_name, configuration, number
             ),
-            Path::ConsoleText {
+            Path::BuildAction {
                 ref job_name,
                 ref number,
                 configuration: None,
-                folder_name: Some(ref folder_name),
+                ref action,
+            } => format!("/job/{}/{}/{}", job_name, number, action),
+            Path::BuildAction {
+                ref job_name,
+                ref number,
+                configuration: Some(ref configuration),
+                ref action,
+            } => format!("/job/{}/{}/{}/{}", job_name, configuration, number, action),
+            Path::WorkflowRuns { ref job_name } => format!("/job/{}/wfapi/runs", job_name),
+            Path::WorkflowRunDescribe {
+                ref job_name,
+                ref number,
+            } => format!("/job/{}/{}/wfapi/describe", job_name, number),
+            Path::WorkflowNodeDescribe {
+                ref job_name,
+                ref number,
+                ref node_id,
             } => format!(
-                "/job/{}/job/{}/{}/consoleText",
-                folder_name.to_string(),
-                job_name.to_string(),
-                number.to_string()
+                "/job/{}/{}/execution/node/{}/wfapi/describe",
+                job_name, number, node_id
             ),
-            Path::ConsoleText {
+            Path::WorkflowNodeLog {
                 ref job_name,
                 ref number,
-                configuration: Some(ref configuration),
-                folder_name: Some(ref folder_name),
+                ref node_id,
             } => format!(
-                "/job/{}/job/{}/{}/{}/consoleText",
-                folder_name.to_string(),
-                job_name.to_string(),
-                configuration.to_string(),
-                number.to_string()
+                "/job/{}/{}/execution/node/{}/wfapi/log",
+                job_name, number, node_id
             ),
-            Path::ConfigXML {
+            Path::TestReport {
+                ref job_name,
+                ref number,
+                configuration: None,
+            } => format!("/job/{}/{}/testReport", job_name, number),
+            Path::TestReport {
                 ref job_name,
-                folder_name: None,
-            } => format!("/job/{}/config.xml", job_name.to_string(),),
-            Path::ConfigXML {
+                ref number,
+                configuration: Some(ref configuration),
+            } => format!("/job/{}/{}/{}/testReport", job_name, configuration, number),
+            Path::Artifact {
                 ref job_name,
-                folder_name: Some(ref folder_name),
+                ref number,
+                configuration: None,
+                relative_path,
+            } => format!(
+                "/job/{}/{}/artifact/{}",
+                job_name,
+                number,
+                encode_relative_path(relative_path)
+            ),
+            Path::Artifact {
+                ref job_name,
+                ref number,
+                configuration: Some(ref configuration),
+                relative_path,
             } => format!(
-                "/job/{}/job/{}/config.xml",
-                folder_name.to_string(),
-                job_name.to_string(),
+                "/job/{}/{}/{}/artifact/{}",
+                job_name,
+                configuration,
+                number,
+                encode_relative_path(relative_path)
             ),
+            Path::ConfigXML { ref job_name } => format!("/job/{}/config.xml", job_name),
             Path::Queue => "/queue".to_string(),
             Path::QueueItem { ref id } => format!("/queue/item/{}", id),
             Path::MavenArtifactRecord {
                 ref job_name,
                 ref number,
                 configuration: None,
-            } => format!(
-                "/job/{}/{}/mavenArtifacts",
-                job_name.to_string(),
-                number.to_string()
-            ),
+            } => format!("/job/{}/{}/mavenArtifacts", job_name, number),
             Path::MavenArtifactRecord {
                 ref job_name,
                 ref number,
                 configuration: Some(ref configuration),
             } => format!(
                 "/job/{}/{}/{}/mavenArtifacts",
-                job_name.to_string(),
-                configuration.to_string(),
-                number.to_string()
+                job_name, configuration, number
             ),
             Path::InFolder {
                 ref folder_name,
                 ref path,
-            } => format!("/job/{}{}", folder_name.to_string(), path.to_string()),
+            } => format!("/job/{}{}", folder_name, path),
             Path::Computers => "/computer/api/json".to_string(),
-            Path::Computer { ref name } => format!("/computer/{}/api/json", name.to_string()),
+            Path::Computer { ref name } => format!("/computer/{}/api/json", name),
             Path::Raw { path } => path.to_string(),
             Path::CrumbIssuer => "/crumbIssuer".to_string(),
+        })
+    }
+}
+
+impl<'a> Path<'a> {
+    /// Path of the object itself, without the folders and views containing it
+    pub(crate) fn leaf(&self) -> &Path<'a> {
+        match self {
+            Path::InFolder { path, .. } | Path::InView { path, .. } => path.leaf(),
+            path => path,
+        }
+    }
+
+    /// Replace the path of the object, keeping the folders and views containing it
+    pub(crate) fn map_leaf<F>(self, f: F) -> Option<Path<'a>>
+    where
+        F: FnOnce(Path<'a>) -> Option<Path<'a>>,
+    {
+        match self {
+            Path::InFolder { folder_name, path } => path.map_leaf(f).map(|path| Path::InFolder {
+                folder_name,
+                path: Box::new(path),
+            }),
+            Path::InView { view_name, path } => path.map_leaf(f).map(|path| Path::InView {
+                view_name,
+                path: Box::new(path),
+            }),
+            path => f(path),
+        }
+    }
+
+    /// Path to an object of a job from its full name, like `folder/job`
+    pub(crate) fn for_job<F>(full_name: &'a str, name_encoded: bool, f: F) -> Path<'a>
+    where
+        F: FnOnce(Name<'a>) -> Path<'a>,
+    {
+        let (folders, job_name) = split_full_name(full_name, name_encoded);
+        Path::in_folders(folders, f(job_name))
+    }
+
+    /// Put a path inside of nested folders, from the outermost one
+    pub(crate) fn in_folders(folders: Vec<Name<'a>>, path: Path<'a>) -> Path<'a> {
+        folders
+            .into_iter()
+            .rev()
+            .fold(path, |path, folder_name| Path::InFolder {
+                folder_name,
+                path: Box::new(path),
+            })
+    }
+
+    /// Split a path into the folders containing the object and the path of the object
+    pub(crate) fn into_folders_and_leaf(self) -> (Vec<Name<'a>>, Path<'a>) {
+        match self {
+            Path::InFolder { folder_name, path } => {
+                let (mut folders, leaf) = path.into_folders_and_leaf();
+                folders.insert(0, folder_name);
+                (folders, leaf)
+            }
+            Path::InView { path, .. } => path.into_folders_and_leaf(),
+            path => (vec![], path),
+        }
+    }
+
+    /// Full name of a job, including the folders containing it
+    pub(crate) fn full_job_name(&self) -> Option<String> {
+        match self {
+            Path::InFolder { folder_name, path } => path
+                .full_job_name()
+                .map(|name| format!("{}/{}", folder_name, name)),
+            Path::InView { path, .. } => path.full_job_name(),
+            Path::Job {
+                name,
+                configuration: None,
+            } => Some(name.to_string()),
+            _ => None,
         }
     }
 }
 
-impl Jenkins {
-    pub(crate) fn url_to_path<'a>(&self, url: &'a str) -> Path<'a> {
-        let path = if url.starts_with(&self.url) {
-            &url[self.url.len()..]
+/// Encode each segment of a relative path, keeping its separators
+fn encode_relative_path(relative_path: &str) -> String {
+    relative_path
+        .split('/')
+        .map(urlencoding::encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Split the full name of a job, like `folder/job`, into its folders and its name
+pub(crate) fn split_full_name(full_name: &str, name_encoded: bool) -> (Vec<Name<'_>>, Name<'_>) {
+    let mut names = full_name.split('/').map(|name| {
+        if name_encoded {
+            Name::UrlEncodedName(name)
         } else {
-            url
-        };
-        let slashes: Vec<usize> = path
-            .char_indices()
-            .filter(|c| c.1 == '/')
-            .map(|c| c.0)
-            .collect();
-
-        match (&path[0..slashes[1]], slashes.len()) {
-            ("/view", 3) => Path::View {
-                name: Name::UrlEncodedName(&path[6..(path.len() - 1)]),
-            },
-            ("/job", 3) => Path::Job {
-                name: Name::UrlEncodedName(&path[5..(path.len() - 1)]),
+            Name::Name(name)
+        }
+    });
+    let job_name = names.next_back().unwrap_or(Name::Name(full_name));
+    (names.collect(), job_name)
+}
+
+impl Jenkins {
+    pub(crate) fn url_to_path<'a>(&self, url: &'a str) -> Result<Path<'a>> {
+        url_to_path(&self.url, self.public_url.as_deref(), url)
+    }
+}
+
+/// Parse an URL provided by Jenkins into a `Path`
+///
+/// Jenkins may emit URLs with its public root URL instead of the URL used to connect to it,
+/// they are mapped back to a path from the root of Jenkins.
+pub(crate) fn url_to_path<'a>(
+    base_url: &str,
+    public_url: Option<&str>,
+    url: &'a str,
+) -> Result<Path<'a>> {
+    let base_urls = [Some(base_url), public_url];
+    let path = path_from_root(base_urls.iter().flatten().copied(), url);
+    parse_path(path).ok_or_else(|| Error::MalformedUrl {
+        url: url.to_string(),
+    })
+}
+
+/// Remove the root URL of Jenkins from `url`, including its context path
+fn path_from_root<'a, 'b, I>(base_urls: I, url: &'a str) -> &'a str
+where
+    I: Iterator<Item = &'b str> + Clone,
+{
+    let starts_path = |rest: &str| rest.is_empty() || rest.starts_with('/');
+    if let Some(rest) = base_urls
+        .clone()
+        .filter_map(|base_url| url.strip_prefix(base_url))
+        .find(|rest| starts_path(rest))
+    {
+        return rest;
+    }
+
+    // URL with an unknown host, only keep its path and remove the context path
+    let path = url_path(url);
+    base_urls
+        .map(|base_url| url_path(base_url).trim_end_matches('/'))
+        .filter(|context_path| !context_path.is_empty())
+        .filter_map(|context_path| path.strip_prefix(context_path))
+        .find(|rest| starts_path(rest))
+        .unwrap_or(path)
+}
+
+/// Path of an absolute URL
+fn url_path(url: &str) -> &str {
+    match url.find("://") {
+        Some(scheme_end) => {
+            let after_scheme = &url[(scheme_end + 3)..];
+            after_scheme
+                .find('/')
+                .map_or("", |path_start| &after_scheme[path_start..])
+        }
+        None => url,
+    }
+}
+
+/// Parse a path from the root of Jenkins into a `Path`
+///
+/// Paths that are not links to a known object are kept as `Path::Raw`, `None` is returned
+/// for malformed links to a known object.
+fn parse_path(path: &str) -> Option<Path<'_>> {
+    let path = path.split(['?', '#']).next().unwrap_or("");
+    let trimmed = path.strip_prefix('/').unwrap_or(path);
+    let trimmed = trimmed.strip_suffix('/').unwrap_or(trimmed);
+    if trimmed.is_empty() {
+        return Some(Path::Home);
+    }
+    let segments: Vec<&str> = trimmed.split('/').collect();
+    match parse_segments(&segments) {
+        Parsed::Path(parsed) => Some(parsed),
+        Parsed::Unknown => Some(Path::Raw { path }),
+        Parsed::Malformed => None,
+    }
+}
+
+/// Result of parsing the segments of a path
+enum Parsed<'a> {
+    Path(Path<'a>),
+    Unknown,
+    Malformed,
+}
+
+fn parse_segments<'a>(segments: &[&'a str]) -> Parsed<'a> {
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Parsed::Malformed;
+    }
+    match segments {
+        ["view"] | ["job"] => Parsed::Malformed,
+        ["view", name] => Parsed::Path(Path::View {
+            name: Name::UrlEncodedName(name),
+        }),
+        ["view", name, rest @ ..] => match parse_segments(rest) {
+            Parsed::Path(path) => Parsed::Path(Path::InView {
+                view_name: Name::UrlEncodedName(name),
+                path: Box::new(path),
+            }),
+            other => other,
+        },
+        ["job", name, rest @ ..] => parse_job_segments(name, rest),
+        ["queue", "item", id] => match id.parse() {
+            Ok(id) => Parsed::Path(Path::QueueItem { id }),
+            Err(_) => Parsed::Malformed,
+        },
+        _ => Parsed::Unknown,
+    }
+}
+
+/// Parse the segments following `/job/<name>`
+fn parse_job_segments<'a>(name: &'a str, rest: &[&'a str]) -> Parsed<'a> {
+    let job_name = Name::UrlEncodedName(name);
+    match rest {
+        [] => Parsed::Path(Path::Job {
+            name: job_name,
+            configuration: None,
+        }),
+        ["job", ..] | ["view", ..] => match parse_segments(rest) {
+            Parsed::Path(path) => Parsed::Path(Path::InFolder {
+                folder_name: job_name,
+                path: Box::new(path),
+            }),
+            other => other,
+        },
+        [number] => Parsed::Path(match build_number(number) {
+            Some(number) => Path::Build {
+                job_name,
+                number,
                 configuration: None,
             },
-            ("/job", 4) => {
-                let last_part = &path[(slashes[2] + 1)..(path.len() - 1)];
-                let number = last_part.parse();
-                if let Ok(number) = number {
-                    Path::Build {
-                        job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        number: build::BuildNumber::Number(number),
-                        configuration: None,
-                    }
-                } else {
-                    Path::Job {
-                        name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        configuration: Some(Name::UrlEncodedName(last_part)),
-                    }
-                }
-            }
-            ("/job", 5) => {
-                if &path[slashes[3]..slashes[4]] == "/mavenArtifacts" {
-                    Path::MavenArtifactRecord {
-                        job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        number: build::BuildNumber::Number(
-                            path[(slashes[3] + 1)..(path.len() - 1)].parse().unwrap(),
-                        ),
-                        configuration: None,
-                    }
-                } else if &path[slashes[2]..slashes[3]] == "/job" {
-                    Path::InFolder {
-                        folder_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        path: Box::new(self.url_to_path(&path[slashes[2]..])),
-                    }
-                } else {
-                    Path::Build {
-                        job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        number: build::BuildNumber::Number(
-                            path[(slashes[3] + 1)..(path.len() - 1)].parse().unwrap(),
-                        ),
-                        configuration: Some(Name::UrlEncodedName(
-                            &path[(slashes[2] + 1)..slashes[3]],
-                        )),
-                    }
-                }
-            }
-            ("/job", 6) => {
-                if &path[slashes[2]..slashes[3]] == "/job" {
-                    Path::InFolder {
-                        folder_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        path: Box::new(self.url_to_path(&path[slashes[2]..])),
-                    }
-                } else {
-                    Path::MavenArtifactRecord {
-                        job_name: Name::UrlEncodedName(&path[5..slashes[2]]),
-                        number: build::BuildNumber::Number(
-                            path[(slashes[3] + 1)..slashes[4]].parse().unwrap(),
-                        ),
-                        configuration: Some(Name::UrlEncodedName(
-                            &path[(slashes[2] + 1)..slashes[3]],
-                        )),
-                    }
-                }
-            }
-            ("/queue", 4) => Path::QueueItem {
-                id: path[(slashes[2] + 1)..(path.len() - 1)].parse().unwrap(),
+            None => Path::Job {
+                name: job_name,
+                configuration: Some(Name::UrlEncodedName(number)),
             },
-            (_, _) => Path::Raw { path },
-        }
+        }),
+        [number, "mavenArtifacts"] => match build_number(number) {
+            Some(number) => Parsed::Path(Path::MavenArtifactRecord {
+                job_name,
+                number,
+                configuration: None,
+            }),
+            None => Parsed::Unknown,
+        },
+        [configuration, number] => match build_number(number) {
+            Some(number) => Parsed::Path(Path::Build {
+                job_name,
+                number,
+                configuration: Some(Name::UrlEncodedName(configuration)),
+            }),
+            None => Parsed::Unknown,
+        },
+        [configuration, number, "mavenArtifacts"] => match build_number(number) {
+            Some(number) => Parsed::Path(Path::MavenArtifactRecord {
+                job_name,
+                number,
+                configuration: Some(Name::UrlEncodedName(configuration)),
+            }),
+            None => Parsed::Unknown,
+        },
+        _ => Parsed::Unknown,
+    }
+}
+
+/// Build number or known alias to a build
+fn build_number(segment: &str) -> Option<build::BuildNumber> {
+    if let Ok(number) = segment.parse() {
+        return Some(build::BuildNumber::Number(number));
+    }
+    match build::BuildNumber::from(segment) {
+        build::BuildNumber::UnknwonAlias(_) => None,
+        number => Some(number),
     }
 }
 
@@ -329,7 +621,7 @@ mod tests {
     fn can_parse_view_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
-        let path = jenkins_client.url_to_path("/view/myview/");
+        let path = jenkins_client.url_to_path("/view/myview/").unwrap();
         assert_eq!(
             path,
             Path::View {
@@ -342,7 +634,7 @@ mod tests {
     fn can_parse_job_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
-        let path = jenkins_client.url_to_path("/job/myjob/");
+        let path = jenkins_client.url_to_path("/job/myjob/").unwrap();
         assert_eq!(
             path,
             Path::Job {
@@ -356,7 +648,7 @@ mod tests {
     fn can_parse_job_with_config_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
-        let path = jenkins_client.url_to_path("/job/myjob/config/");
+        let path = jenkins_client.url_to_path("/job/myjob/config/").unwrap();
         assert_eq!(
             path,
             Path::Job {
@@ -366,11 +658,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_parse_path_with_context_path() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://none:8080/jenkins/")
+            .build()
+            .unwrap();
+
+        let expected = Path::Job {
+            name: Name::UrlEncodedName("myjob"),
+            configuration: None,
+        };
+        assert_eq!(
+            jenkins_client
+                .url_to_path("http://none:8080/jenkins/job/myjob/")
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            jenkins_client.url_to_path("/jenkins/job/myjob/").unwrap(),
+            expected
+        );
+        assert_eq!(
+            jenkins_client
+                .url_to_path("https://jenkins.example.com/jenkins/job/myjob/")
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn can_parse_path_with_public_url() {
+        let jenkins_client = crate::JenkinsBuilder::new("http://jenkins.internal:8080")
+            .with_public_url("https://ci.example.com/jenkins/")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            jenkins_client
+                .url_to_path("https://ci.example.com/jenkins/job/myjob/1/")
+                .unwrap(),
+            Path::Build {
+                job_name: Name::UrlEncodedName("myjob"),
+                number: build::BuildNumber::Number(1),
+                configuration: None
+            }
+        );
+        assert_eq!(
+            jenkins_client
+                .url_to_path("http://jenkins.internal:8080/queue/item/4/")
+                .unwrap(),
+            Path::QueueItem { id: 4 }
+        );
+    }
+
+    #[test]
+    fn can_follow_links_with_public_url() {
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .with_public_url("https://ci.example.com/jenkins")
+            .build()
+            .unwrap();
+
+        let build_mock = mockito::mock("GET", "/job/public-url/3/api/json?depth=1")
+            .with_body("{}")
+            .create();
+
+        let short_build: build::ShortBuild = serde_json::from_str(
+            r#"{"number": 3, "url": "https://ci.example.com/jenkins/job/public-url/3/"}"#,
+        )
+        .unwrap();
+        let _ = short_build.get_full_build(&jenkins_client);
+
+        build_mock.assert();
+    }
+
     #[test]
     fn can_parse_build_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
-        let path = jenkins_client.url_to_path("/job/myjob/1/");
+        let path = jenkins_client.url_to_path("/job/myjob/1/").unwrap();
         assert_eq!(
             path,
             Path::Build {
@@ -385,7 +750,7 @@ mod tests {
     fn can_parse_build_with_config_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
-        let path = jenkins_client.url_to_path("/job/myjob/config/1/");
+        let path = jenkins_client.url_to_path("/job/myjob/config/1/").unwrap();
         assert_eq!(
             path,
             Path::Build {
@@ -400,7 +765,7 @@ mod tests {
     fn can_parse_unknown_path() {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
-        let path = jenkins_client.url_to_path("/unknown/path/");
+        let path = jenkins_client.url_to_path("/unknown/path/").unwrap();
         assert_eq!(
             path,
             Path::Raw {
@@ -414,7 +779,7 @@ mod tests {
         let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
 
         let path_url = format!("{}/job/myjob/", JENKINS_URL);
-        let path = jenkins_client.url_to_path(&path_url);
+        let path = jenkins_client.url_to_path(&path_url).unwrap();
         assert_eq!(
             path,
             Path::Job {
@@ -423,4 +788,149 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn can_parse_paths_in_nested_folders() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client
+            .url_to_path("/job/a/job/b/job/c/42/")
+            .unwrap();
+        assert_eq!(
+            path,
+            Path::InFolder {
+                folder_name: Name::UrlEncodedName("a"),
+                path: Box::new(Path::InFolder {
+                    folder_name: Name::UrlEncodedName("b"),
+                    path: Box::new(Path::Build {
+                        job_name: Name::UrlEncodedName("c"),
+                        number: build::BuildNumber::Number(42),
+                        configuration: None
+                    })
+                })
+            }
+        );
+        assert_eq!(path.to_string(), "/job/a/job/b/job/c/42");
+        assert_eq!(
+            path.leaf(),
+            &Path::Build {
+                job_name: Name::UrlEncodedName("c"),
+                number: build::BuildNumber::Number(42),
+                configuration: None
+            }
+        );
+    }
+
+    #[test]
+    fn can_parse_multibranch_branch_path() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client
+            .url_to_path("/job/repo/job/feature%252Fx/lastBuild/")
+            .unwrap();
+        assert_eq!(path.to_string(), "/job/repo/job/feature%252Fx/lastBuild");
+        assert_eq!(
+            path.leaf(),
+            &Path::Build {
+                job_name: Name::UrlEncodedName("feature%252Fx"),
+                number: build::BuildNumber::LastBuild,
+                configuration: None
+            }
+        );
+    }
+
+    #[test]
+    fn can_parse_matrix_configuration_in_folder() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client
+            .url_to_path("/job/folder/job/matrix/label=linux/3/mavenArtifacts/")
+            .unwrap();
+        assert_eq!(
+            path,
+            Path::InFolder {
+                folder_name: Name::UrlEncodedName("folder"),
+                path: Box::new(Path::MavenArtifactRecord {
+                    job_name: Name::UrlEncodedName("matrix"),
+                    number: build::BuildNumber::Number(3),
+                    configuration: Some(Name::UrlEncodedName("label=linux"))
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn can_parse_views_in_folders_and_views() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client
+            .url_to_path("/job/folder/view/myview/")
+            .unwrap();
+        assert_eq!(
+            path,
+            Path::InFolder {
+                folder_name: Name::UrlEncodedName("folder"),
+                path: Box::new(Path::View {
+                    name: Name::UrlEncodedName("myview")
+                })
+            }
+        );
+
+        let path = jenkins_client
+            .url_to_path("/view/parent/view/child/job/myjob/")
+            .unwrap();
+        assert_eq!(path.to_string(), "/view/parent/view/child/job/myjob");
+        assert_eq!(path.full_job_name(), Some("myjob".to_string()));
+    }
+
+    #[test]
+    fn can_map_leaf_of_path() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        let path = jenkins_client.url_to_path("/job/a/job/b/").unwrap();
+        assert_eq!(path.full_job_name(), Some("a/b".to_string()));
+        let path = path.map_leaf(|path| match path {
+            Path::Job { name, .. } => Some(Path::JobEnable { name }),
+            _ => None,
+        });
+        assert_eq!(path.unwrap().to_string(), "/job/a/job/b/enable");
+    }
+
+    #[test]
+    fn can_parse_home_and_queue_paths() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        assert_eq!(jenkins_client.url_to_path(JENKINS_URL).unwrap(), Path::Home);
+        assert_eq!(
+            jenkins_client.url_to_path("/queue/item/12/?x=1").unwrap(),
+            Path::QueueItem { id: 12 }
+        );
+        assert_eq!(
+            jenkins_client
+                .url_to_path("/job/myjob/1/testReport/")
+                .unwrap(),
+            Path::Raw {
+                path: "/job/myjob/1/testReport/"
+            }
+        );
+    }
+
+    #[test]
+    fn can_reject_malformed_paths() {
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL).build().unwrap();
+
+        for url in &[
+            "/job/",
+            "/view/",
+            "/job//1/",
+            "/job/a/job/",
+            "/view/a/view/",
+            "/queue/item/abc/",
+        ] {
+            match jenkins_client.url_to_path(url) {
+                Err(Error::MalformedUrl { url: found }) => assert_eq!(&found, url),
+                other => panic!("expected a malformed url for {}, got {:?}", url, other),
+            }
+        }
+    }
 }
@@ -0,0 +1,40 @@
+use std::io::Read;
+
+use serde::de::DeserializeOwned;
+
+use super::{Error, Result};
+
+/// Read the body of a response from Jenkins
+pub(crate) trait JsonResponse {
+    /// Deserialize the JSON body of the response
+    fn json_body<T: DeserializeOwned>(self) -> Result<T>;
+}
+
+impl JsonResponse for reqwest::blocking::Response {
+    fn json_body<T: DeserializeOwned>(mut self) -> Result<T> {
+        let path = self.url().path().to_string();
+        // Reading with `Read` applies the read timeout of the client to each read
+        let mut body = vec![];
+        let _ = self
+            .read_to_end(&mut body)
+            .map_err(|err| Error::Transport(Box::new(err)))?;
+        serde_json::from_slice(&body)
+            .map_err(|serde_error| Error::Deserialize { path, serde_error })
+    }
+}
+
+/// Read the body of a response from Jenkins with an asynchronous client
+#[cfg(feature = "async")]
+pub(crate) trait AsyncJsonResponse {
+    /// Deserialize the JSON body of the response
+    async fn json_body<T: DeserializeOwned>(self) -> Result<T>;
+}
+
+#[cfg(feature = "async")]
+impl AsyncJsonResponse for reqwest::Response {
+    async fn json_body<T: DeserializeOwned>(self) -> Result<T> {
+        let path = self.url().path().to_string();
+        let body = self.text().await?;
+        serde_json::from_str(&body).map_err(|serde_error| Error::Deserialize { path, serde_error })
+    }
+}
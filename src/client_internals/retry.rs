@@ -0,0 +1,408 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use log::warn;
+use reqwest::{
+    blocking::{RequestBuilder, Response},
+    StatusCode,
+};
+
+use super::csrf::NO_VALID_CRUMB;
+use super::{Error, Jenkins, Result};
+
+/// Policy to retry requests that failed because Jenkins or the network was unavailable
+///
+/// Requests are retried after a transport error, or when Jenkins replies with one of the
+/// retryable statuses. The delay between two attempts grows exponentially, with a random
+/// jitter.
+///
+/// Only idempotent requests are retried by default. Requests that trigger an action in
+/// Jenkins, such as building a job, are retried only after calling
+/// [`retry_non_idempotent_requests`](#method.retry_non_idempotent_requests).
+///
+/// ```rust
+///# extern crate jenkins_api;
+///#
+///# use std::time::Duration;
+///# use jenkins_api::JenkinsBuilder;
+///# use jenkins_api::client::RetryPolicy;
+///#
+///# fn example_function() {
+///     let jenkins = JenkinsBuilder::new("http://localhost:8080")
+///         .with_retry_policy(
+///             RetryPolicy::default()
+///                 .with_max_attempts(5)
+///                 .with_backoff(Duration::from_millis(200), Duration::from_secs(5)),
+///         )
+///         .build()
+///         .unwrap();
+///# }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retryable_statuses: Vec<u16>,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable_statuses: vec![502, 503, 504],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Maximum number of times a request is sent, including the first attempt
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry, doubled on each retry up to `max_backoff`
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Wait exactly the backoff delay between attempts, without a random jitter
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = false;
+        self
+    }
+
+    /// HTTP statuses after which a request is retried
+    pub fn with_retryable_statuses(mut self, statuses: &[u16]) -> Self {
+        self.retryable_statuses = statuses.to_vec();
+        self
+    }
+
+    /// Also retry requests that are not idempotent, such as POSTs and build triggers
+    pub fn retry_non_idempotent_requests(mut self) -> Self {
+        self.retry_non_idempotent = true;
+        self
+    }
+
+    pub(crate) fn max_attempts(&self, idempotent: bool) -> u32 {
+        if idempotent || self.retry_non_idempotent {
+            self.max_attempts
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&status.as_u16())
+    }
+
+    /// Delay to wait after the failed attempt number `attempt`, starting at 1
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff / 2 + backoff.mul_f64(random_fraction() / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Jenkins {
+    /// Send a request, retrying it according to the `RetryPolicy` of the client
+    ///
+    /// When `with_csrf` is set, a crumb is added to each attempt, and a new crumb is
+    /// fetched once if Jenkins rejects it.
+    ///
+    /// A client using the login form logs in again once if Jenkins replies with a 401 or a
+    /// 403, as its session may have expired.
+    pub(super) fn send_with_retry(
+        &self,
+        request_builder: RequestBuilder,
+        with_csrf: bool,
+        idempotent: bool,
+    ) -> Result<Response> {
+        let max_attempts = self
+            .retry_policy
+            .as_ref()
+            .map_or(1, |retry_policy| retry_policy.max_attempts(idempotent));
+        let mut attempt = 1;
+        let mut crumb_refreshed = false;
+        let mut logged_in_again = false;
+        let mut current = request_builder;
+        loop {
+            let next = current.try_clone();
+            let request = if with_csrf {
+                self.add_csrf_to_request(current)?
+            } else {
+                current
+            };
+            let outcome = self.send(request);
+            let next = match next {
+                Some(next) => next,
+                None => return Self::error_for_status(outcome?),
+            };
+
+            if attempt < max_attempts && self.is_retryable(&outcome) {
+                let backoff = self
+                    .retry_policy
+                    .as_ref()
+                    .map_or(Duration::from_secs(0), |retry_policy| {
+                        retry_policy.backoff(attempt)
+                    });
+                warn!(
+                    "attempt {} of {} failed, retrying in {:?}",
+                    attempt, max_attempts, backoff
+                );
+                std::thread::sleep(backoff);
+                attempt += 1;
+                current = next;
+                continue;
+            }
+
+            let response = outcome?;
+            let status = response.status();
+            let crumb_rejected = with_csrf && !crumb_refreshed && status == StatusCode::FORBIDDEN;
+            // The session of the login form may have expired
+            let session_expired = !logged_in_again
+                && self.auth.is_logged_in()
+                && (status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN);
+            if crumb_rejected || session_expired {
+                let path = response.url().path().to_string();
+                let body = response.text()?;
+                if crumb_rejected && body.contains(NO_VALID_CRUMB) {
+                    warn!("crumb rejected by Jenkins, getting a new one");
+                    self.crumb.invalidate();
+                    crumb_refreshed = true;
+                    current = next;
+                    continue;
+                }
+                if session_expired {
+                    warn!("got an error: {}, logging in again", status);
+                    self.auth.log_out();
+                    self.crumb.invalidate();
+                    logged_in_again = true;
+                    current = next;
+                    continue;
+                }
+                return Err(Error::from_status(status, &path, &body));
+            }
+            return Self::error_for_status(response);
+        }
+    }
+
+    fn is_retryable(&self, outcome: &Result<Response>) -> bool {
+        match (&self.retry_policy, outcome) {
+            (Some(retry_policy), Ok(response)) => {
+                retry_policy.is_retryable_status(response.status())
+            }
+            (Some(_), Err(Error::Transport(_))) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Random number in `[0, 1)`, good enough to spread retries
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use reqwest::blocking::{Request, Response};
+
+    use super::*;
+    use crate::client_internals::{Error, Result, Transport};
+
+    static JENKINS_URL: &str = "http://none:8080";
+
+    /// Transport replying with each status of `statuses` in turn, then with a 200
+    #[derive(Debug, Default)]
+    struct FlakyTransport {
+        statuses: Mutex<Vec<u16>>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    impl FlakyTransport {
+        fn new(statuses: &[u16]) -> Arc<Self> {
+            Arc::new(FlakyTransport {
+                statuses: Mutex::new(statuses.iter().rev().cloned().collect()),
+                requests: Mutex::new(vec![]),
+            })
+        }
+    }
+
+    impl Transport for FlakyTransport {
+        fn execute(&self, request: Request) -> Result<Response> {
+            let crumb = request
+                .headers()
+                .get("jenkins-crumb")
+                .map(|value| value.to_str().unwrap().to_string());
+            self.requests.lock().unwrap().push(format!(
+                "{} {} {:?}",
+                request.method(),
+                request.url().path(),
+                crumb
+            ));
+            if request.url().path() == "/crumbIssuer/api/json" {
+                return Ok(http::Response::new(
+                    r#"{"crumb":"abc","crumbRequestField":"Jenkins-Crumb"}"#,
+                )
+                .into());
+            }
+            let status = self.statuses.lock().unwrap().pop().unwrap_or(200);
+            if status == 0 {
+                return Err(Error::Transport("connection reset".into()));
+            }
+            let body = match status {
+                200 => r#"{"items":[]}"#,
+                403 => "HTTP ERROR 403 No valid crumb was included in the request",
+                _ => "",
+            };
+            Ok(http::Response::builder()
+                .status(status)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::default().with_backoff(Duration::from_millis(0), Duration::from_millis(0))
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(350))
+            .without_jitter();
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(100), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn backoff_has_jitter() {
+        let policy =
+            RetryPolicy::default().with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+        for _ in 0..20 {
+            let backoff = policy.backoff(2);
+            assert!(backoff >= Duration::from_millis(100));
+            assert!(backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn can_retry_get() {
+        let transport = FlakyTransport::new(&[503, 0]);
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .with_retry_policy(policy())
+            .build()
+            .unwrap();
+
+        let queue = jenkins_client.get_queue();
+
+        assert!(queue.is_ok());
+        assert_eq!(transport.requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn stops_retrying_after_max_attempts() {
+        let transport = FlakyTransport::new(&[502, 502, 502, 502]);
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .with_retry_policy(policy().with_max_attempts(2))
+            .build()
+            .unwrap();
+
+        let queue = jenkins_client.get_queue();
+
+        match queue {
+            Err(Error::ServerError { status: 502, .. }) => (),
+            _ => panic!("expected a server error, got {:?}", queue),
+        }
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn doesnt_retry_without_policy() {
+        let transport = FlakyTransport::new(&[503]);
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.get_queue().is_err());
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn doesnt_retry_post_by_default() {
+        let transport = FlakyTransport::new(&[503]);
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .with_retry_policy(policy())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.poll_scm_job("myjob").is_err());
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn can_retry_post_when_enabled() {
+        let transport = FlakyTransport::new(&[503]);
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .with_retry_policy(policy().retry_non_idempotent_requests())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.poll_scm_job("myjob").is_ok());
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn refreshes_rejected_crumb() {
+        let transport = FlakyTransport::new(&[403]);
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.poll_scm_job("myjob").is_ok());
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec![
+                "GET /crumbIssuer/api/json None",
+                r#"POST /job/myjob/polling Some("abc")"#,
+                "GET /crumbIssuer/api/json None",
+                r#"POST /job/myjob/polling Some("abc")"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt::Debug;
+
+use reqwest::blocking::{Client, Request, Response};
+
+use super::Result;
+
+/// Transport used by the `Jenkins` client to send its requests
+///
+/// The default transport is a `reqwest::blocking::Client`. Another transport can be
+/// set with [`JenkinsBuilder::with_transport`](../struct.JenkinsBuilder.html#method.with_transport),
+/// for example to answer requests in process during tests, to record them, or to
+/// go through an HTTP middleware.
+///
+/// A `Response` can be built from an `http::Response`:
+///
+/// ```rust
+///# extern crate jenkins_api;
+///# extern crate http;
+///# extern crate reqwest;
+///#
+/// use jenkins_api::client::{Result, Transport};
+/// use reqwest::blocking::{Request, Response};
+///
+/// #[derive(Debug)]
+/// struct AlwaysOk;
+///
+/// impl Transport for AlwaysOk {
+///     fn execute(&self, _request: Request) -> Result<Response> {
+///         Ok(http::Response::new("{}").into())
+///     }
+/// }
+/// ```
+pub trait Transport: Debug + Send + Sync {
+    /// Send a `Request` to Jenkins and return its `Response`
+    fn execute(&self, request: Request) -> Result<Response>;
+}
+
+impl Transport for Client {
+    fn execute(&self, request: Request) -> Result<Response> {
+        Ok(Client::execute(self, request)?)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn execute(&self, request: Request) -> Result<Response> {
+        (**self).execute(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    static JENKINS_URL: &str = "http://none:8080";
+
+    #[derive(Debug, Default)]
+    struct RecordingTransport {
+        requests: Mutex<Vec<String>>,
+    }
+
+    impl Transport for RecordingTransport {
+        fn execute(&self, request: Request) -> Result<Response> {
+            let crumb = request
+                .headers()
+                .get("jenkins-crumb")
+                .map(|value| value.to_str().unwrap().to_string());
+            self.requests.lock().unwrap().push(format!(
+                "{} {} {:?}",
+                request.method(),
+                request.url(),
+                crumb
+            ));
+            let body = match request.url().path() {
+                "/crumbIssuer/api/json" => r#"{"crumb":"abc","crumbRequestField":"Jenkins-Crumb"}"#,
+                "/queue/api/json" => r#"{"items":[]}"#,
+                _ => "",
+            };
+            Ok(http::Response::new(body).into())
+        }
+    }
+
+    #[test]
+    fn can_get_through_transport() {
+        let transport = Arc::new(RecordingTransport::default());
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .build()
+            .unwrap();
+
+        let queue = jenkins_client.get_queue();
+
+        assert!(queue.is_ok());
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec!["GET http://none:8080/queue/api/json?depth=1 None"]
+        );
+    }
+
+    #[test]
+    fn can_post_with_csrf_through_transport() {
+        let transport = Arc::new(RecordingTransport::default());
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(transport.clone())
+            .build()
+            .unwrap();
+
+        let response = jenkins_client.poll_scm_job("myjob");
+
+        assert!(response.is_ok());
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            vec![
+                "GET http://none:8080/crumbIssuer/api/json?depth=1 None",
+                r#"POST http://none:8080/job/myjob/polling Some("abc")"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn can_get_error_status_from_transport() {
+        #[derive(Debug)]
+        struct NotFound;
+        impl Transport for NotFound {
+            fn execute(&self, _request: Request) -> Result<Response> {
+                Ok(http::Response::builder()
+                    .status(404)
+                    .body("")
+                    .unwrap()
+                    .into())
+            }
+        }
+        let jenkins_client = crate::JenkinsBuilder::new(JENKINS_URL)
+            .with_transport(NotFound)
+            .build()
+            .unwrap();
+
+        assert!(jenkins_client.get_home().is_err());
+    }
+}
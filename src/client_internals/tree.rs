@@ -1,3 +1,6 @@
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
 use serde::{Serialize, Serializer};
 
 /// Jenkins tree query parameter
@@ -7,6 +10,8 @@ pub struct TreeQueryParam {
     keyname: Option<String>,
     /// Children keys
     subkeys: Vec<TreeQueryParam>,
+    /// Range of the elements of a list to read, like `{0,10}`
+    range: Option<String>,
 }
 impl Serialize for TreeQueryParam {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -16,18 +21,22 @@ impl Serialize for TreeQueryParam {
         serializer.serialize_str(&self.to_string())
     }
 }
-impl ToString for TreeQueryParam {
-    fn to_string(&self) -> String {
-        match (self.keyname.as_ref(), self.subkeys.len()) {
+impl fmt::Display for TreeQueryParam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&match (self.keyname.as_ref(), self.subkeys.len()) {
+            (Some(keyname), 0) if self.range.is_some() => {
+                format!("{}{}", keyname, self.range.as_deref().unwrap_or_default())
+            }
             (Some(keyname), 0) => keyname.clone(),
             (Some(keyname), _) => format!(
-                "{}[{}]",
+                "{}[{}]{}",
                 keyname,
                 self.subkeys
                     .iter()
                     .map(TreeQueryParam::to_string)
                     .collect::<Vec<_>>()
-                    .join(",")
+                    .join(","),
+                self.range.as_deref().unwrap_or_default()
             ),
             (None, _) => self
                 .subkeys
@@ -35,7 +44,7 @@ impl ToString for TreeQueryParam {
                 .map(TreeQueryParam::to_string)
                 .collect::<Vec<_>>()
                 .join(","),
-        }
+        })
     }
 }
 
@@ -61,6 +70,7 @@ impl TreeBuilder {
             tree: TreeQueryParam {
                 keyname: None,
                 subkeys: vec![],
+                range: None,
             },
         }
     }
@@ -75,6 +85,7 @@ impl TreeBuilder {
             tree: TreeQueryParam {
                 keyname: Some(name.to_string()),
                 subkeys: vec![],
+                range: None,
             },
         }
     }
@@ -82,27 +93,50 @@ impl TreeBuilder {
     pub fn with_subfield<T: Into<TreeQueryParam>>(self, subfield: T) -> Self {
         self.with_field(subfield)
     }
+    /// Only read the elements of the list in `range`, to read a large list in slices
+    ///
+    /// ```
+    /// jenkins_api::client::TreeBuilder::object("builds")
+    ///     .with_subfield("number")
+    ///     .with_range(10..20)
+    ///     .build();
+    /// ```
+    pub fn with_range<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(start) => start.to_string(),
+            Bound::Excluded(start) => (start + 1).to_string(),
+            Bound::Unbounded => String::new(),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => (end + 1).to_string(),
+            Bound::Excluded(end) => end.to_string(),
+            Bound::Unbounded => String::new(),
+        };
+        self.tree.range = Some(format!("{{{},{}}}", start, end));
+        self
+    }
     /// Build the `TreeQueryParam`
     pub fn build(self) -> TreeQueryParam {
         self.tree
     }
 }
-impl Into<TreeQueryParam> for TreeBuilder {
-    fn into(self) -> TreeQueryParam {
-        self.build()
+impl From<TreeBuilder> for TreeQueryParam {
+    fn from(val: TreeBuilder) -> Self {
+        val.build()
     }
 }
-impl<'a> Into<TreeQueryParam> for &'a str {
-    fn into(self) -> TreeQueryParam {
+impl From<&str> for TreeQueryParam {
+    fn from(val: &str) -> Self {
         TreeQueryParam {
-            keyname: Some(self.to_string()),
+            keyname: Some(val.to_string()),
             subkeys: vec![],
+            range: None,
         }
     }
 }
-impl Into<Option<super::AdvancedQuery>> for TreeQueryParam {
-    fn into(self) -> Option<super::AdvancedQuery> {
-        Some(super::AdvancedQuery::Tree(self))
+impl From<TreeQueryParam> for Option<super::AdvancedQuery> {
+    fn from(val: TreeQueryParam) -> Self {
+        Some(super::AdvancedQuery::Tree(val))
     }
 }
 impl Default for TreeBuilder {
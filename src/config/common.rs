@@ -0,0 +1,497 @@
+use serde::{Deserialize, Serialize};
+
+use super::xml::{
+    child_text, child_value, element_order, has_only_attributes, has_only_children, in_order,
+    optional_child, other_elements, plugin, XmlElement,
+};
+use super::XmlModel;
+use crate::client::Result;
+
+/// A property of a job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JobProperty {
+    /// `jenkins.model.BuildDiscarderProperty` with a `hudson.tasks.LogRotator` strategy
+    BuildDiscarder(BuildDiscarder),
+    /// `hudson.model.ParametersDefinitionProperty`
+    Parameters(Vec<ParameterDefinition>),
+    /// Another property
+    Other(XmlElement),
+}
+
+const BUILD_DISCARDER_PROPERTY: &str = "jenkins.model.BuildDiscarderProperty";
+const LOG_ROTATOR: &str = "hudson.tasks.LogRotator";
+const PARAMETERS_DEFINITION_PROPERTY: &str = "hudson.model.ParametersDefinitionProperty";
+
+impl XmlModel for JobProperty {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        match element.name.as_str() {
+            BUILD_DISCARDER_PROPERTY => {
+                if let Some(strategy) = element.child("strategy").filter(|strategy| {
+                    strategy.attribute("class") == Some(LOG_ROTATOR)
+                        && has_only_children(strategy, BuildDiscarder::FIELDS)
+                        && has_only_children(element, &["strategy"])
+                }) {
+                    return Ok(JobProperty::BuildDiscarder(BuildDiscarder::from_element(
+                        strategy,
+                    )?));
+                }
+            }
+            PARAMETERS_DEFINITION_PROPERTY => {
+                if let Some(definitions) = element
+                    .child("parameterDefinitions")
+                    .filter(|_| has_only_children(element, &["parameterDefinitions"]))
+                {
+                    return Ok(JobProperty::Parameters(
+                        definitions
+                            .elements()
+                            .map(ParameterDefinition::from_element)
+                            .collect::<Result<_>>()?,
+                    ));
+                }
+            }
+            _ => (),
+        }
+        Ok(JobProperty::Other(element.clone()))
+    }
+
+    fn to_element(&self) -> XmlElement {
+        match self {
+            JobProperty::BuildDiscarder(build_discarder) => {
+                XmlElement::new(BUILD_DISCARDER_PROPERTY).with_child(build_discarder.to_element())
+            }
+            JobProperty::Parameters(definitions) => XmlElement::new(PARAMETERS_DEFINITION_PROPERTY)
+                .with_child(
+                    XmlElement::new("parameterDefinitions")
+                        .with_children(definitions.iter().map(XmlModel::to_element)),
+                ),
+            JobProperty::Other(element) => element.clone(),
+        }
+    }
+}
+
+/// Discard old builds and artifacts, `None` keeps them forever
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuildDiscarder {
+    /// Days to keep builds
+    pub days_to_keep: Option<u32>,
+    /// Maximum number of builds to keep
+    pub num_to_keep: Option<u32>,
+    /// Days to keep artifacts
+    pub artifact_days_to_keep: Option<u32>,
+    /// Maximum number of builds to keep with their artifacts
+    pub artifact_num_to_keep: Option<u32>,
+}
+
+impl BuildDiscarder {
+    const FIELDS: &'static [&'static str] = &[
+        "daysToKeep",
+        "numToKeep",
+        "artifactDaysToKeep",
+        "artifactNumToKeep",
+    ];
+}
+
+/// Jenkins uses `-1` for no limit
+fn limit(element: &XmlElement, name: &str) -> Result<Option<u32>> {
+    Ok(child_value::<i64>(element, name)?
+        .filter(|value| *value >= 0)
+        .map(|value| value as u32))
+}
+
+fn limit_element(name: &str, value: Option<u32>) -> XmlElement {
+    XmlElement::with_text(name, &value.map_or(-1, i64::from).to_string())
+}
+
+impl XmlModel for BuildDiscarder {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        Ok(BuildDiscarder {
+            days_to_keep: limit(element, "daysToKeep")?,
+            num_to_keep: limit(element, "numToKeep")?,
+            artifact_days_to_keep: limit(element, "artifactDaysToKeep")?,
+            artifact_num_to_keep: limit(element, "artifactNumToKeep")?,
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        XmlElement::new("strategy")
+            .with_attribute("class", LOG_ROTATOR)
+            .with_child(limit_element("daysToKeep", self.days_to_keep))
+            .with_child(limit_element("numToKeep", self.num_to_keep))
+            .with_child(limit_element(
+                "artifactDaysToKeep",
+                self.artifact_days_to_keep,
+            ))
+            .with_child(limit_element(
+                "artifactNumToKeep",
+                self.artifact_num_to_keep,
+            ))
+    }
+}
+
+/// Definition of a parameter of a job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParameterDefinition {
+    /// `hudson.model.StringParameterDefinition`
+    String {
+        /// Name of the parameter
+        name: String,
+        /// Description of the parameter
+        description: Option<String>,
+        /// Default value
+        default_value: Option<String>,
+        /// Trim the value
+        trim: bool,
+    },
+    /// `hudson.model.TextParameterDefinition`
+    Text {
+        /// Name of the parameter
+        name: String,
+        /// Description of the parameter
+        description: Option<String>,
+        /// Default value
+        default_value: Option<String>,
+        /// Trim the value
+        trim: bool,
+    },
+    /// `hudson.model.BooleanParameterDefinition`
+    Boolean {
+        /// Name of the parameter
+        name: String,
+        /// Description of the parameter
+        description: Option<String>,
+        /// Default value
+        default_value: bool,
+    },
+    /// `hudson.model.ChoiceParameterDefinition`
+    Choice {
+        /// Name of the parameter
+        name: String,
+        /// Description of the parameter
+        description: Option<String>,
+        /// Allowed values, the first one is the default
+        choices: Vec<String>,
+    },
+    /// `hudson.model.PasswordParameterDefinition`
+    Password {
+        /// Name of the parameter
+        name: String,
+        /// Description of the parameter
+        description: Option<String>,
+        /// Default value, as stored by Jenkins
+        default_value: Option<String>,
+    },
+    /// Another parameter type
+    Other(XmlElement),
+}
+
+const STRING_PARAMETER: &str = "hudson.model.StringParameterDefinition";
+const TEXT_PARAMETER: &str = "hudson.model.TextParameterDefinition";
+const BOOLEAN_PARAMETER: &str = "hudson.model.BooleanParameterDefinition";
+const CHOICE_PARAMETER: &str = "hudson.model.ChoiceParameterDefinition";
+const PASSWORD_PARAMETER: &str = "hudson.model.PasswordParameterDefinition";
+
+impl XmlModel for ParameterDefinition {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        let name = child_text(element, "name").unwrap_or_default();
+        let description = child_text(element, "description");
+        let with_default = &["name", "description", "defaultValue"];
+        let with_trim = &["name", "description", "defaultValue", "trim"];
+        Ok(match element.name.as_str() {
+            STRING_PARAMETER if has_only_children(element, with_trim) => {
+                ParameterDefinition::String {
+                    name,
+                    description,
+                    default_value: child_text(element, "defaultValue"),
+                    trim: child_value(element, "trim")?.unwrap_or(false),
+                }
+            }
+            TEXT_PARAMETER if has_only_children(element, with_trim) => ParameterDefinition::Text {
+                name,
+                description,
+                default_value: child_text(element, "defaultValue"),
+                trim: child_value(element, "trim")?.unwrap_or(false),
+            },
+            BOOLEAN_PARAMETER if has_only_children(element, with_default) => {
+                ParameterDefinition::Boolean {
+                    name,
+                    description,
+                    default_value: child_value(element, "defaultValue")?.unwrap_or(false),
+                }
+            }
+            PASSWORD_PARAMETER if has_only_children(element, with_default) => {
+                ParameterDefinition::Password {
+                    name,
+                    description,
+                    default_value: child_text(element, "defaultValue"),
+                }
+            }
+            CHOICE_PARAMETER if has_only_children(element, &["name", "description", "choices"]) => {
+                ParameterDefinition::Choice {
+                    name,
+                    description,
+                    choices: element
+                        .child("choices")
+                        .map(|choices| {
+                            // choices are stored in a list, or in an array inside of a list
+                            choices
+                                .elements()
+                                .flat_map(|choice| match choice.name.as_str() {
+                                    "string" => vec![choice.text()],
+                                    _ => choice.elements().map(XmlElement::text).collect(),
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            }
+            _ => ParameterDefinition::Other(element.clone()),
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        let common = |class: &str, name: &str, description: &Option<String>| {
+            XmlElement::new(class)
+                .with_child(XmlElement::with_text("name", name))
+                .with_children(optional_child("description", description))
+        };
+        match self {
+            ParameterDefinition::String {
+                name,
+                description,
+                default_value,
+                trim,
+            } => common(STRING_PARAMETER, name, description)
+                .with_children(optional_child("defaultValue", default_value))
+                .with_child(XmlElement::with_text("trim", &trim.to_string())),
+            ParameterDefinition::Text {
+                name,
+                description,
+                default_value,
+                trim,
+            } => common(TEXT_PARAMETER, name, description)
+                .with_children(optional_child("defaultValue", default_value))
+                .with_child(XmlElement::with_text("trim", &trim.to_string())),
+            ParameterDefinition::Boolean {
+                name,
+                description,
+                default_value,
+            } => common(BOOLEAN_PARAMETER, name, description).with_child(XmlElement::with_text(
+                "defaultValue",
+                &default_value.to_string(),
+            )),
+            ParameterDefinition::Password {
+                name,
+                description,
+                default_value,
+            } => common(PASSWORD_PARAMETER, name, description)
+                .with_children(optional_child("defaultValue", default_value)),
+            ParameterDefinition::Choice {
+                name,
+                description,
+                choices,
+            } => common(CHOICE_PARAMETER, name, description).with_child(
+                XmlElement::new("choices")
+                    .with_attribute("class", "java.util.Arrays$ArrayList")
+                    .with_child(
+                        XmlElement::new("a")
+                            .with_attribute("class", "string-array")
+                            .with_children(
+                                choices
+                                    .iter()
+                                    .map(|choice| XmlElement::with_text("string", choice)),
+                            ),
+                    ),
+            ),
+            ParameterDefinition::Other(element) => element.clone(),
+        }
+    }
+}
+
+/// Source Control Management of a job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum Scm {
+    /// No SCM, `hudson.scm.NullSCM`
+    #[default]
+    None,
+    /// `hudson.plugins.git.GitSCM`
+    Git(GitScm),
+    /// Another SCM, kept with its `scm` element
+    Other(XmlElement),
+}
+
+const NULL_SCM: &str = "hudson.scm.NullSCM";
+const GIT_SCM: &str = "hudson.plugins.git.GitSCM";
+const USER_REMOTE_CONFIG: &str = "hudson.plugins.git.UserRemoteConfig";
+const BRANCH_SPEC: &str = "hudson.plugins.git.BranchSpec";
+
+/// Git repositories and branches to build
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitScm {
+    /// Remote repositories
+    pub remotes: Vec<GitRemote>,
+    /// Branches to build, like `*/master`
+    pub branches: Vec<String>,
+    /// Other elements of the configuration, kept as is
+    pub other_elements: Vec<XmlElement>,
+    /// Plugin that saved the configuration
+    pub plugin: Option<String>,
+    /// Names of the elements of the configuration in the order they were read
+    pub element_order: Vec<String>,
+}
+
+/// A Git remote repository
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitRemote {
+    /// URL of the repository
+    pub url: String,
+    /// Name of the remote
+    pub name: Option<String>,
+    /// Refspec to fetch
+    pub refspec: Option<String>,
+    /// ID of the credentials used to fetch
+    pub credentials_id: Option<String>,
+}
+
+const GIT_REMOTE_FIELDS: &[&str] = &["name", "refspec", "url", "credentialsId"];
+
+impl XmlModel for Scm {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        Ok(match element.attribute("class") {
+            Some(NULL_SCM)
+                if element.elements().next().is_none()
+                    && has_only_attributes(element, &["class"]) =>
+            {
+                Scm::None
+            }
+            Some(GIT_SCM)
+                if has_only_attributes(element, &["class", "plugin"])
+                    && element.child("userRemoteConfigs").is_none_or(|remotes| {
+                        remotes.elements().all(|remote| {
+                            remote.name == USER_REMOTE_CONFIG
+                                && has_only_children(remote, GIT_REMOTE_FIELDS)
+                        })
+                    })
+                    && element.child("branches").is_none_or(|branches| {
+                        branches.elements().all(|branch| {
+                            branch.name == BRANCH_SPEC && has_only_children(branch, &["name"])
+                        })
+                    }) =>
+            {
+                Scm::Git(GitScm {
+                    remotes: element
+                        .child("userRemoteConfigs")
+                        .into_iter()
+                        .flat_map(XmlElement::elements)
+                        .map(|remote| GitRemote {
+                            url: child_text(remote, "url").unwrap_or_default(),
+                            name: child_text(remote, "name"),
+                            refspec: child_text(remote, "refspec"),
+                            credentials_id: child_text(remote, "credentialsId"),
+                        })
+                        .collect(),
+                    branches: element
+                        .child("branches")
+                        .into_iter()
+                        .flat_map(XmlElement::elements)
+                        .map(|branch| child_text(branch, "name").unwrap_or_default())
+                        .collect(),
+                    other_elements: other_elements(element, &["userRemoteConfigs", "branches"]),
+                    plugin: plugin(element),
+                    element_order: element_order(element),
+                })
+            }
+            _ => Scm::Other(element.clone()),
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        match self {
+            Scm::None => XmlElement::new("scm").with_attribute("class", NULL_SCM),
+            Scm::Git(git) => {
+                let elements = vec![
+                    XmlElement::new("userRemoteConfigs").with_children(git.remotes.iter().map(
+                        |remote| {
+                            XmlElement::new(USER_REMOTE_CONFIG)
+                                .with_children(optional_child("name", &remote.name))
+                                .with_children(optional_child("refspec", &remote.refspec))
+                                .with_child(XmlElement::with_text("url", &remote.url))
+                                .with_children(optional_child(
+                                    "credentialsId",
+                                    &remote.credentials_id,
+                                ))
+                        },
+                    )),
+                    XmlElement::new("branches").with_children(git.branches.iter().map(|branch| {
+                        XmlElement::new(BRANCH_SPEC)
+                            .with_child(XmlElement::with_text("name", branch))
+                    })),
+                ]
+                .into_iter()
+                .chain(git.other_elements.iter().cloned())
+                .collect();
+                XmlElement::new("scm")
+                    .with_attribute("class", GIT_SCM)
+                    .with_plugin(&git.plugin)
+                    .with_children(in_order(elements, &git.element_order))
+            }
+            Scm::Other(element) => element.clone(),
+        }
+    }
+}
+
+/// A trigger starting builds of a job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Trigger {
+    /// `hudson.triggers.TimerTrigger`, build periodically
+    Timer {
+        /// Cron-like schedule
+        spec: String,
+    },
+    /// `hudson.triggers.SCMTrigger`, poll the SCM for changes
+    ScmPolling {
+        /// Cron-like schedule
+        spec: String,
+        /// Ignore changes notified by post-commit hooks
+        ignore_post_commit_hooks: bool,
+    },
+    /// Another trigger
+    Other(XmlElement),
+}
+
+const TIMER_TRIGGER: &str = "hudson.triggers.TimerTrigger";
+const SCM_TRIGGER: &str = "hudson.triggers.SCMTrigger";
+
+impl XmlModel for Trigger {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        let spec = child_text(element, "spec").unwrap_or_default();
+        Ok(match element.name.as_str() {
+            TIMER_TRIGGER if has_only_children(element, &["spec"]) => Trigger::Timer { spec },
+            SCM_TRIGGER if has_only_children(element, &["spec", "ignorePostCommitHooks"]) => {
+                Trigger::ScmPolling {
+                    spec,
+                    ignore_post_commit_hooks: child_value(element, "ignorePostCommitHooks")?
+                        .unwrap_or(false),
+                }
+            }
+            _ => Trigger::Other(element.clone()),
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        match self {
+            Trigger::Timer { spec } => {
+                XmlElement::new(TIMER_TRIGGER).with_child(XmlElement::with_text("spec", spec))
+            }
+            Trigger::ScmPolling {
+                spec,
+                ignore_post_commit_hooks,
+            } => XmlElement::new(SCM_TRIGGER)
+                .with_child(XmlElement::with_text("spec", spec))
+                .with_child(XmlElement::with_text(
+                    "ignorePostCommitHooks",
+                    &ignore_post_commit_hooks.to_string(),
+                )),
+            Trigger::Other(element) => element.clone(),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use super::xml::{
+    child_text, child_value, element_order, in_order, optional_text_child, other_elements, plugin,
+    XmlElement,
+};
+use super::{JobConfig, JobProperty, Scm, Trigger, XmlModel};
+use crate::client::Result;
+
+/// Configuration of a `FreeStyleProject`, read from and written to its config.xml file
+///
+/// Elements that are not typed are kept in `other_elements`, and all elements are written back
+/// in the order they were read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FreeStyleProjectConfig {
+    /// Description of the job
+    pub description: Option<String>,
+    /// Is the job disabled
+    pub disabled: bool,
+    /// Can builds run concurrently
+    pub concurrent_build: bool,
+    /// Label expression restricting where builds can run
+    pub assigned_node: Option<String>,
+    /// Properties of the job
+    pub properties: Vec<JobProperty>,
+    /// Source Control Management
+    pub scm: Scm,
+    /// Triggers starting builds
+    pub triggers: Vec<Trigger>,
+    /// Build steps
+    pub builders: Vec<Builder>,
+    /// Post-build actions
+    pub publishers: Vec<Publisher>,
+    /// Other elements of the configuration, kept as is
+    pub other_elements: Vec<XmlElement>,
+    /// Plugin that saved the configuration, from the `plugin` attribute of the root element
+    pub plugin: Option<String>,
+    /// Names of the elements of the configuration in the order they were read
+    pub element_order: Vec<String>,
+}
+
+const FREESTYLE_FIELDS: &[&str] = &[
+    "description",
+    "disabled",
+    "concurrentBuild",
+    "assignedNode",
+    "properties",
+    "scm",
+    "triggers",
+    "builders",
+    "publishers",
+];
+
+impl JobConfig for FreeStyleProjectConfig {
+    const ROOT_ELEMENT: &'static str = "project";
+
+    fn from_root_element(element: &XmlElement) -> Result<Self> {
+        Ok(FreeStyleProjectConfig {
+            description: child_text(element, "description"),
+            disabled: child_value(element, "disabled")?.unwrap_or(false),
+            concurrent_build: child_value(element, "concurrentBuild")?.unwrap_or(false),
+            assigned_node: child_text(element, "assignedNode"),
+            properties: super::children_from_element(element, "properties")?,
+            scm: element
+                .child("scm")
+                .map(Scm::from_element)
+                .transpose()?
+                .unwrap_or_default(),
+            triggers: super::children_from_element(element, "triggers")?,
+            builders: super::children_from_element(element, "builders")?,
+            publishers: super::children_from_element(element, "publishers")?,
+            other_elements: other_elements(element, FREESTYLE_FIELDS),
+            plugin: plugin(element),
+            element_order: element_order(element),
+        })
+    }
+
+    fn to_root_element(&self) -> XmlElement {
+        let order = &self.element_order;
+        let elements = optional_text_child("description", &self.description, order)
+            .into_iter()
+            .chain(vec![
+                super::children_to_element("properties", &self.properties),
+                self.scm.to_element(),
+            ])
+            .chain(optional_text_child(
+                "assignedNode",
+                &self.assigned_node,
+                order,
+            ))
+            .chain(vec![
+                XmlElement::with_text("disabled", &self.disabled.to_string()),
+                super::children_to_element("triggers", &self.triggers),
+                XmlElement::with_text("concurrentBuild", &self.concurrent_build.to_string()),
+                super::children_to_element("builders", &self.builders),
+                super::children_to_element("publishers", &self.publishers),
+            ])
+            .chain(self.other_elements.iter().cloned())
+            .collect();
+        XmlElement::new(Self::ROOT_ELEMENT)
+            .with_plugin(&self.plugin)
+            .with_children(in_order(elements, order))
+    }
+}
+
+/// A build step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Builder {
+    /// `hudson.tasks.Shell`, run a shell script
+    Shell {
+        /// Script to run
+        command: String,
+        /// Other elements of the build step, kept as is
+        other_elements: Vec<XmlElement>,
+    },
+    /// `hudson.tasks.BatchFile`, run a Windows batch script
+    BatchFile {
+        /// Script to run
+        command: String,
+        /// Other elements of the build step, kept as is
+        other_elements: Vec<XmlElement>,
+    },
+    /// Another build step
+    Other(XmlElement),
+}
+
+const SHELL: &str = "hudson.tasks.Shell";
+const BATCH_FILE: &str = "hudson.tasks.BatchFile";
+
+impl XmlModel for Builder {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        let command = child_text(element, "command").unwrap_or_default();
+        Ok(match element.name.as_str() {
+            SHELL => Builder::Shell {
+                command,
+                other_elements: other_elements(element, &["command"]),
+            },
+            BATCH_FILE => Builder::BatchFile {
+                command,
+                other_elements: other_elements(element, &["command"]),
+            },
+            _ => Builder::Other(element.clone()),
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        match self {
+            Builder::Shell {
+                command,
+                other_elements,
+            } => XmlElement::new(SHELL)
+                .with_child(XmlElement::with_text("command", command))
+                .with_children(other_elements.iter().cloned()),
+            Builder::BatchFile {
+                command,
+                other_elements,
+            } => XmlElement::new(BATCH_FILE)
+                .with_child(XmlElement::with_text("command", command))
+                .with_children(other_elements.iter().cloned()),
+            Builder::Other(element) => element.clone(),
+        }
+    }
+}
+
+/// A post-build action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Publisher {
+    /// `hudson.tasks.ArtifactArchiver`, archive artifacts
+    ArtifactArchiver {
+        /// Files to archive, as a comma separated list of Ant patterns
+        artifacts: String,
+        /// Don't fail the build if no artifacts are found
+        allow_empty_archive: bool,
+        /// Fingerprint archived files
+        fingerprint: bool,
+        /// Other elements of the post-build action, kept as is
+        other_elements: Vec<XmlElement>,
+        /// Names of the elements of the post-build action in the order they were read
+        element_order: Vec<String>,
+    },
+    /// Another post-build action
+    Other(XmlElement),
+}
+
+const ARTIFACT_ARCHIVER: &str = "hudson.tasks.ArtifactArchiver";
+
+impl XmlModel for Publisher {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        Ok(match element.name.as_str() {
+            ARTIFACT_ARCHIVER => Publisher::ArtifactArchiver {
+                artifacts: child_text(element, "artifacts").unwrap_or_default(),
+                allow_empty_archive: child_value(element, "allowEmptyArchive")?.unwrap_or(false),
+                fingerprint: child_value(element, "fingerprint")?.unwrap_or(false),
+                other_elements: other_elements(
+                    element,
+                    &["artifacts", "allowEmptyArchive", "fingerprint"],
+                ),
+                element_order: element_order(element),
+            },
+            _ => Publisher::Other(element.clone()),
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        match self {
+            Publisher::ArtifactArchiver {
+                artifacts,
+                allow_empty_archive,
+                fingerprint,
+                other_elements,
+                element_order,
+            } => {
+                let elements = vec![
+                    XmlElement::with_text("artifacts", artifacts),
+                    XmlElement::with_text("allowEmptyArchive", &allow_empty_archive.to_string()),
+                    XmlElement::with_text("fingerprint", &fingerprint.to_string()),
+                ]
+                .into_iter()
+                .chain(other_elements.iter().cloned())
+                .collect();
+                XmlElement::new(ARTIFACT_ARCHIVER).with_children(in_order(elements, element_order))
+            }
+            Publisher::Other(element) => element.clone(),
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! Typed models of the config.xml file of jobs
+//!
+//! ```rust
+//!# extern crate jenkins_api;
+//!#
+//!# use jenkins_api::JenkinsBuilder;
+//!# use jenkins_api::config::{Builder, FreeStyleProjectConfig, JobConfig};
+//!# use jenkins_api::job::Job;
+//!#
+//!# fn example_function() -> Result<(), jenkins_api::Error> {
+//!     let jenkins = JenkinsBuilder::new("http://localhost:8080").build()?;
+//!     let job = jenkins.get_job("my job")?;
+//!     let mut config: FreeStyleProjectConfig = job.get_config(&jenkins)?;
+//!     config.builders.push(Builder::Shell {
+//!         command: "make test".to_string(),
+//!         other_elements: vec![],
+//!     });
+//!     job.update_config(&jenkins, &config)?;
+//!#     Ok(())
+//!# }
+//! ```
+
+use crate::client::{Error, Result};
+
+mod xml;
+pub use self::xml::{XmlElement, XmlNode};
+mod common;
+pub use self::common::{
+    BuildDiscarder, GitRemote, GitScm, JobProperty, ParameterDefinition, Scm, Trigger,
+};
+mod freestyle;
+pub use self::freestyle::{Builder, FreeStyleProjectConfig, Publisher};
+mod pipeline;
+pub use self::pipeline::{FlowDefinition, WorkflowJobConfig};
+
+/// Trait implemented by the configuration of a type of job
+pub trait JobConfig: Sized {
+    /// Name of the root element of the config.xml file
+    const ROOT_ELEMENT: &'static str;
+
+    /// Read the configuration from the root element of the config.xml file
+    fn from_root_element(element: &XmlElement) -> Result<Self>;
+
+    /// Write the configuration as the root element of a config.xml file
+    fn to_root_element(&self) -> XmlElement;
+
+    /// Parse the configuration from a config.xml file
+    fn from_xml(xml: &str) -> Result<Self> {
+        let element = XmlElement::parse(xml)?;
+        if element.name != Self::ROOT_ELEMENT {
+            return Err(Error::InvalidXml(
+                format!(
+                    "expected a <{}> config, got <{}>",
+                    Self::ROOT_ELEMENT,
+                    element.name
+                )
+                .into(),
+            ));
+        }
+        Self::from_root_element(&element)
+    }
+
+    /// Write the configuration as a config.xml file
+    fn to_xml(&self) -> Result<String> {
+        self.to_root_element().to_xml()
+    }
+}
+
+/// Conversion of a part of a configuration from and to an XML element
+pub(crate) trait XmlModel: Sized {
+    fn from_element(element: &XmlElement) -> Result<Self>;
+    fn to_element(&self) -> XmlElement;
+}
+
+/// Read each child of the element `name` in `element`
+fn children_from_element<T: XmlModel>(element: &XmlElement, name: &str) -> Result<Vec<T>> {
+    element
+        .child(name)
+        .into_iter()
+        .flat_map(XmlElement::elements)
+        .map(T::from_element)
+        .collect()
+}
+
+/// Write an element `name` with each item of `children` as a child
+fn children_to_element<T: XmlModel>(name: &str, children: &[T]) -> XmlElement {
+    XmlElement::new(name).with_children(children.iter().map(XmlModel::to_element))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static FREESTYLE_XML: &str = r#"<?xml version='1.1' encoding='UTF-8'?>
+<project>
+  <actions/>
+  <description>Build &amp; test</description>
+  <keepDependencies>false</keepDependencies>
+  <properties>
+    <jenkins.model.BuildDiscarderProperty>
+      <strategy class="hudson.tasks.LogRotator">
+        <daysToKeep>-1</daysToKeep>
+        <numToKeep>10</numToKeep>
+        <artifactDaysToKeep>-1</artifactDaysToKeep>
+        <artifactNumToKeep>-1</artifactNumToKeep>
+      </strategy>
+    </jenkins.model.BuildDiscarderProperty>
+    <hudson.model.ParametersDefinitionProperty>
+      <parameterDefinitions>
+        <hudson.model.StringParameterDefinition>
+          <name>TARGET</name>
+          <defaultValue>all</defaultValue>
+          <trim>false</trim>
+        </hudson.model.StringParameterDefinition>
+        <hudson.model.ChoiceParameterDefinition>
+          <name>MODE</name>
+          <description>Build mode</description>
+          <choices class="java.util.Arrays$ArrayList">
+            <a class="string-array">
+              <string>debug</string>
+              <string>release</string>
+            </a>
+          </choices>
+        </hudson.model.ChoiceParameterDefinition>
+        <com.cloudbees.plugins.credentials.CredentialsParameterDefinition plugin="credentials@2.3.14">
+          <name>CREDS</name>
+          <credentialType>com.cloudbees.plugins.credentials.common.StandardCredentials</credentialType>
+          <required>false</required>
+        </com.cloudbees.plugins.credentials.CredentialsParameterDefinition>
+      </parameterDefinitions>
+    </hudson.model.ParametersDefinitionProperty>
+  </properties>
+  <scm class="hudson.plugins.git.GitSCM" plugin="git@4.4.5">
+    <configVersion>2</configVersion>
+    <userRemoteConfigs>
+      <hudson.plugins.git.UserRemoteConfig>
+        <url>https://example.com/repo.git</url>
+        <credentialsId>git-creds</credentialsId>
+      </hudson.plugins.git.UserRemoteConfig>
+    </userRemoteConfigs>
+    <branches>
+      <hudson.plugins.git.BranchSpec>
+        <name>*/master</name>
+      </hudson.plugins.git.BranchSpec>
+    </branches>
+    <doGenerateSubmoduleConfigurations>false</doGenerateSubmoduleConfigurations>
+  </scm>
+  <canRoam>true</canRoam>
+  <disabled>false</disabled>
+  <triggers>
+    <hudson.triggers.SCMTrigger>
+      <spec>H/5 * * * *</spec>
+      <ignorePostCommitHooks>false</ignorePostCommitHooks>
+    </hudson.triggers.SCMTrigger>
+  </triggers>
+  <concurrentBuild>true</concurrentBuild>
+  <builders>
+    <hudson.tasks.Shell>
+      <command>make &lt;all&gt;</command>
+      <configuredLocalRules/>
+    </hudson.tasks.Shell>
+    <org.jenkinsci.plugins.custom.Step>
+      <value>1</value>
+    </org.jenkinsci.plugins.custom.Step>
+  </builders>
+  <publishers>
+    <hudson.tasks.ArtifactArchiver>
+      <artifacts>target/*.jar</artifacts>
+      <allowEmptyArchive>false</allowEmptyArchive>
+      <onlyIfSuccessful>false</onlyIfSuccessful>
+      <fingerprint>true</fingerprint>
+    </hudson.tasks.ArtifactArchiver>
+  </publishers>
+  <buildWrappers/>
+</project>"#;
+
+    #[test]
+    fn can_read_freestyle_config() {
+        let config = FreeStyleProjectConfig::from_xml(FREESTYLE_XML).unwrap();
+
+        assert_eq!(config.description.as_deref(), Some("Build & test"));
+        assert!(!config.disabled);
+        assert!(config.concurrent_build);
+        assert_eq!(
+            config.properties[0],
+            JobProperty::BuildDiscarder(BuildDiscarder {
+                num_to_keep: Some(10),
+                ..Default::default()
+            })
+        );
+        match &config.properties[1] {
+            JobProperty::Parameters(parameters) => {
+                assert_eq!(
+                    parameters[0],
+                    ParameterDefinition::String {
+                        name: "TARGET".to_string(),
+                        description: None,
+                        default_value: Some("all".to_string()),
+                        trim: false,
+                    }
+                );
+                assert_eq!(
+                    parameters[1],
+                    ParameterDefinition::Choice {
+                        name: "MODE".to_string(),
+                        description: Some("Build mode".to_string()),
+                        choices: vec!["debug".to_string(), "release".to_string()],
+                    }
+                );
+                match &parameters[2] {
+                    ParameterDefinition::Other(element) => assert_eq!(
+                        element.name,
+                        "com.cloudbees.plugins.credentials.CredentialsParameterDefinition"
+                    ),
+                    other => panic!("expected an untyped parameter, got {:?}", other),
+                }
+            }
+            other => panic!("expected parameters, got {:?}", other),
+        }
+        match &config.scm {
+            Scm::Git(git) => {
+                assert_eq!(git.remotes[0].url, "https://example.com/repo.git");
+                assert_eq!(git.remotes[0].credentials_id.as_deref(), Some("git-creds"));
+                assert_eq!(git.branches, vec!["*/master"]);
+                assert_eq!(git.other_elements.len(), 2);
+            }
+            other => panic!("expected a git scm, got {:?}", other),
+        }
+        assert_eq!(
+            config.triggers,
+            vec![Trigger::ScmPolling {
+                spec: "H/5 * * * *".to_string(),
+                ignore_post_commit_hooks: false,
+            }]
+        );
+        assert_eq!(
+            config.builders[0],
+            Builder::Shell {
+                command: "make <all>".to_string(),
+                other_elements: vec![XmlElement::new("configuredLocalRules")],
+            }
+        );
+        match &config.builders[1] {
+            Builder::Other(element) => {
+                assert_eq!(element.name, "org.jenkinsci.plugins.custom.Step")
+            }
+            other => panic!("expected an untyped builder, got {:?}", other),
+        }
+        match &config.publishers[0] {
+            Publisher::ArtifactArchiver {
+                artifacts,
+                fingerprint,
+                ..
+            } => {
+                assert_eq!(artifacts, "target/*.jar");
+                assert!(fingerprint);
+            }
+            other => panic!("expected an artifact archiver, got {:?}", other),
+        }
+        assert_eq!(
+            config
+                .other_elements
+                .iter()
+                .map(|element| element.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["actions", "keepDependencies", "canRoam", "buildWrappers"]
+        );
+    }
+
+    #[test]
+    fn can_round_trip_freestyle_config() {
+        let config = FreeStyleProjectConfig::from_xml(FREESTYLE_XML).unwrap();
+
+        assert_eq!(config.to_xml().unwrap(), FREESTYLE_XML);
+    }
+
+    #[test]
+    fn can_round_trip_pipeline_configs() {
+        let script_xml = r#"<?xml version='1.1' encoding='UTF-8'?>
+<flow-definition plugin="workflow-job@2.40">
+  <actions/>
+  <description></description>
+  <keepDependencies>false</keepDependencies>
+  <properties/>
+  <definition class="org.jenkinsci.plugins.workflow.cps.CpsFlowDefinition" plugin="workflow-cps@2.87">
+    <script>pipeline {
+  agent any
+}</script>
+    <sandbox>true</sandbox>
+  </definition>
+  <triggers/>
+  <disabled>true</disabled>
+</flow-definition>"#;
+        let script = WorkflowJobConfig::from_xml(script_xml).unwrap();
+        assert!(script.disabled);
+        assert_eq!(script.description, None);
+        assert_eq!(
+            script.definition,
+            FlowDefinition::Script {
+                script: "pipeline {\n  agent any\n}".to_string(),
+                sandbox: true,
+                plugin: Some("workflow-cps@2.87".to_string()),
+            }
+        );
+        assert_eq!(script.plugin.as_deref(), Some("workflow-job@2.40"));
+        assert_eq!(script.to_xml().unwrap(), script_xml);
+
+        let from_scm_xml = r#"<?xml version='1.1' encoding='UTF-8'?>
+<flow-definition>
+  <properties/>
+  <definition class="org.jenkinsci.plugins.workflow.cps.CpsScmFlowDefinition">
+    <scm class="hudson.scm.NullSCM"/>
+    <scriptPath>ci/Jenkinsfile</scriptPath>
+    <lightweight>true</lightweight>
+  </definition>
+  <disabled>false</disabled>
+</flow-definition>"#;
+        let from_scm = WorkflowJobConfig::from_xml(from_scm_xml).unwrap();
+        assert_eq!(
+            from_scm.definition,
+            FlowDefinition::Scm {
+                scm: Scm::None,
+                script_path: "ci/Jenkinsfile".to_string(),
+                lightweight: true,
+                plugin: None,
+            }
+        );
+        assert_eq!(from_scm.to_xml().unwrap(), from_scm_xml);
+    }
+
+    #[test]
+    fn cant_read_other_job_type() {
+        match WorkflowJobConfig::from_xml(FREESTYLE_XML) {
+            Err(Error::InvalidXml(_)) => (),
+            other => panic!("expected an invalid xml error, got {:?}", other),
+        }
+        assert!(FreeStyleProjectConfig::from_xml("<project>").is_err());
+    }
+
+    #[test]
+    fn can_get_and_update_config() {
+        use crate::job::Job;
+
+        let jenkins_client = crate::JenkinsBuilder::new(&mockito::server_url())
+            .disable_csrf()
+            .build()
+            .unwrap();
+
+        let _get_job_mock = mockito::mock("GET", "/job/pipeline/api/json?depth=1")
+            .with_body(format!(
+                r#"{{"name": "pipeline", "displayName": "pipeline", "actions": [], "url": "{}/job/pipeline/"}}"#,
+                mockito::server_url()
+            ))
+            .create();
+        let _get_config_mock = mockito::mock("GET", "/job/pipeline/config.xml/api/json?depth=1")
+            .with_body("<flow-definition><disabled>false</disabled></flow-definition>")
+            .create();
+        let update_mock = mockito::mock("POST", "/job/pipeline/config.xml")
+            .match_body(mockito::Matcher::Regex(
+                "<disabled>true</disabled>".to_string(),
+            ))
+            .create();
+
+        let job = jenkins_client.get_job("pipeline").unwrap();
+        let mut config: WorkflowJobConfig = job.get_config(&jenkins_client).unwrap();
+        assert!(!config.disabled);
+        config.disabled = true;
+        job.update_config(&jenkins_client, &config).unwrap();
+
+        update_mock.assert();
+    }
+}
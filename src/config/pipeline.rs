@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use super::xml::{
+    child_text, child_value, element_order, has_only_attributes, has_only_children, in_order,
+    optional_text_child, other_elements, plugin, XmlElement,
+};
+use super::{JobConfig, JobProperty, Scm, XmlModel};
+use crate::client::Result;
+
+/// Configuration of a `WorkflowJob`, read from and written to its config.xml file
+///
+/// Elements that are not typed are kept in `other_elements`, and all elements are written back
+/// in the order they were read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowJobConfig {
+    /// Description of the job
+    pub description: Option<String>,
+    /// Is the job disabled
+    pub disabled: bool,
+    /// Properties of the job
+    pub properties: Vec<JobProperty>,
+    /// Definition of the pipeline
+    pub definition: FlowDefinition,
+    /// Other elements of the configuration, kept as is
+    pub other_elements: Vec<XmlElement>,
+    /// Plugin that saved the configuration, from the `plugin` attribute of the root element
+    pub plugin: Option<String>,
+    /// Names of the elements of the configuration in the order they were read
+    pub element_order: Vec<String>,
+}
+
+const WORKFLOW_FIELDS: &[&str] = &["description", "disabled", "properties", "definition"];
+
+impl JobConfig for WorkflowJobConfig {
+    const ROOT_ELEMENT: &'static str = "flow-definition";
+
+    fn from_root_element(element: &XmlElement) -> Result<Self> {
+        Ok(WorkflowJobConfig {
+            description: child_text(element, "description"),
+            disabled: child_value(element, "disabled")?.unwrap_or(false),
+            properties: super::children_from_element(element, "properties")?,
+            definition: element
+                .child("definition")
+                .map(FlowDefinition::from_element)
+                .transpose()?
+                .unwrap_or_default(),
+            other_elements: other_elements(element, WORKFLOW_FIELDS),
+            plugin: plugin(element),
+            element_order: element_order(element),
+        })
+    }
+
+    fn to_root_element(&self) -> XmlElement {
+        let elements = optional_text_child("description", &self.description, &self.element_order)
+            .into_iter()
+            .chain(vec![
+                super::children_to_element("properties", &self.properties),
+                self.definition.to_element(),
+                XmlElement::with_text("disabled", &self.disabled.to_string()),
+            ])
+            .chain(self.other_elements.iter().cloned())
+            .collect();
+        XmlElement::new(Self::ROOT_ELEMENT)
+            .with_plugin(&self.plugin)
+            .with_children(in_order(elements, &self.element_order))
+    }
+}
+
+/// Definition of a pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FlowDefinition {
+    /// `org.jenkinsci.plugins.workflow.cps.CpsFlowDefinition`, a script in the job
+    /// configuration
+    Script {
+        /// Pipeline script
+        script: String,
+        /// Run the script in the Groovy sandbox
+        sandbox: bool,
+        /// Plugin that saved the definition
+        plugin: Option<String>,
+    },
+    /// `org.jenkinsci.plugins.workflow.cps.CpsScmFlowDefinition`, a script read from a SCM
+    Scm {
+        /// SCM containing the script
+        scm: Scm,
+        /// Path to the script in the SCM
+        script_path: String,
+        /// Only read the script instead of doing a full checkout
+        lightweight: bool,
+        /// Plugin that saved the definition
+        plugin: Option<String>,
+    },
+    /// Another definition, kept with its `definition` element
+    Other(XmlElement),
+}
+
+impl Default for FlowDefinition {
+    fn default() -> Self {
+        FlowDefinition::Script {
+            script: String::new(),
+            sandbox: true,
+            plugin: None,
+        }
+    }
+}
+
+const CPS_FLOW_DEFINITION: &str = "org.jenkinsci.plugins.workflow.cps.CpsFlowDefinition";
+const CPS_SCM_FLOW_DEFINITION: &str = "org.jenkinsci.plugins.workflow.cps.CpsScmFlowDefinition";
+
+impl XmlModel for FlowDefinition {
+    fn from_element(element: &XmlElement) -> Result<Self> {
+        if !has_only_attributes(element, &["class", "plugin"]) {
+            return Ok(FlowDefinition::Other(element.clone()));
+        }
+        Ok(match element.attribute("class") {
+            Some(CPS_FLOW_DEFINITION) if has_only_children(element, &["script", "sandbox"]) => {
+                FlowDefinition::Script {
+                    script: element
+                        .child("script")
+                        .map(XmlElement::text)
+                        .unwrap_or_default(),
+                    sandbox: child_value(element, "sandbox")?.unwrap_or(false),
+                    plugin: plugin(element),
+                }
+            }
+            Some(CPS_SCM_FLOW_DEFINITION)
+                if has_only_children(element, &["scm", "scriptPath", "lightweight"]) =>
+            {
+                FlowDefinition::Scm {
+                    scm: element
+                        .child("scm")
+                        .map(Scm::from_element)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    script_path: child_text(element, "scriptPath").unwrap_or_default(),
+                    lightweight: child_value(element, "lightweight")?.unwrap_or(false),
+                    plugin: plugin(element),
+                }
+            }
+            _ => FlowDefinition::Other(element.clone()),
+        })
+    }
+
+    fn to_element(&self) -> XmlElement {
+        match self {
+            FlowDefinition::Script {
+                script,
+                sandbox,
+                plugin,
+            } => XmlElement::new("definition")
+                .with_attribute("class", CPS_FLOW_DEFINITION)
+                .with_plugin(plugin)
+                .with_child(XmlElement::with_text("script", script))
+                .with_child(XmlElement::with_text("sandbox", &sandbox.to_string())),
+            FlowDefinition::Scm {
+                scm,
+                script_path,
+                lightweight,
+                plugin,
+            } => XmlElement::new("definition")
+                .with_attribute("class", CPS_SCM_FLOW_DEFINITION)
+                .with_plugin(plugin)
+                .with_child(scm.to_element())
+                .with_child(XmlElement::with_text("scriptPath", script_path))
+                .with_child(XmlElement::with_text(
+                    "lightweight",
+                    &lightweight.to_string(),
+                )),
+            FlowDefinition::Other(element) => element.clone(),
+        }
+    }
+}
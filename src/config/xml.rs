@@ -0,0 +1,276 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use xml::reader::{ParserConfig, XmlEvent};
+use xml::writer::{EmitterConfig, XmlEvent as WriterEvent};
+
+use crate::client::{Error, Result};
+
+/// XML declaration written by Jenkins at the start of config.xml files
+const XML_DECLARATION: &str = "<?xml version='1.1' encoding='UTF-8'?>";
+
+/// A node in an XML document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum XmlNode {
+    /// An element
+    Element(XmlElement),
+    /// Text content
+    Text(String),
+}
+
+/// An XML element, used to keep the parts of a config.xml file that are not typed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct XmlElement {
+    /// Name of the element
+    pub name: String,
+    /// Attributes of the element, in order
+    pub attributes: Vec<(String, String)>,
+    /// Children of the element, in order
+    pub children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    /// Create an empty element
+    pub fn new(name: &str) -> Self {
+        XmlElement {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Create an element with only a text content
+    pub fn with_text(name: &str, text: &str) -> Self {
+        XmlElement::new(name).push_text(text)
+    }
+
+    /// Add an attribute to this element
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a child element to this element
+    pub fn with_child(mut self, child: XmlElement) -> Self {
+        self.children.push(XmlNode::Element(child));
+        self
+    }
+
+    /// Add child elements to this element
+    pub fn with_children<I>(mut self, children: I) -> Self
+    where
+        I: IntoIterator<Item = XmlElement>,
+    {
+        self.children
+            .extend(children.into_iter().map(XmlNode::Element));
+        self
+    }
+
+    /// Add the `plugin` attribute to this element, if present
+    pub(crate) fn with_plugin(self, plugin: &Option<String>) -> Self {
+        match plugin {
+            Some(plugin) => self.with_attribute("plugin", plugin),
+            None => self,
+        }
+    }
+
+    fn push_text(mut self, text: &str) -> Self {
+        if !text.is_empty() {
+            self.children.push(XmlNode::Text(text.to_string()));
+        }
+        self
+    }
+
+    /// Value of the attribute `name`
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Child elements of this element
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|child| match child {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// First child element named `name`
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Text content of this element
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                XmlNode::Text(text) => Some(text.as_str()),
+                XmlNode::Element(_) => None,
+            })
+            .collect()
+    }
+
+    /// Parse an XML document into its root element
+    pub fn parse(xml: &str) -> Result<Self> {
+        let reader = ParserConfig::new()
+            .cdata_to_characters(true)
+            .ignore_comments(true)
+            .coalesce_characters(true)
+            .create_reader(xml.as_bytes());
+        let mut stack: Vec<XmlElement> = vec![];
+        for event in reader {
+            match event.map_err(|err| Error::InvalidXml(Box::new(err)))? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => stack.push(XmlElement {
+                    name: name.to_string(),
+                    attributes: attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.name.to_string(), attribute.value))
+                        .collect(),
+                    children: vec![],
+                }),
+                XmlEvent::EndElement { .. } => {
+                    let element = stack
+                        .pop()
+                        .ok_or_else(|| Error::InvalidXml("unbalanced element".into()))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(XmlNode::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+                XmlEvent::Characters(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.children.push(XmlNode::Text(text));
+                    }
+                }
+                _ => (),
+            }
+        }
+        Err(Error::InvalidXml("no root element".into()))
+    }
+
+    /// Write this element as an XML document
+    pub fn to_xml(&self) -> Result<String> {
+        let mut output = format!("{}\n", XML_DECLARATION).into_bytes();
+        {
+            let mut writer = EmitterConfig::new()
+                .perform_indent(true)
+                .pad_self_closing(false)
+                .write_document_declaration(false)
+                .create_writer(&mut output);
+            self.write(&mut writer)
+                .map_err(|err| Error::InvalidXml(Box::new(err)))?;
+        }
+        String::from_utf8(output).map_err(|err| Error::InvalidXml(Box::new(err)))
+    }
+
+    fn write<W: std::io::Write>(
+        &self,
+        writer: &mut xml::EventWriter<W>,
+    ) -> xml::writer::Result<()> {
+        let mut start = WriterEvent::start_element(self.name.as_str());
+        for (name, value) in &self.attributes {
+            start = start.attr(name.as_str(), value);
+        }
+        writer.write(start)?;
+        for child in &self.children {
+            match child {
+                XmlNode::Element(element) => element.write(writer)?,
+                XmlNode::Text(text) => writer.write(WriterEvent::characters(text))?,
+            }
+        }
+        writer.write(WriterEvent::end_element())
+    }
+}
+
+/// Children of `element` that are not named in `known`
+pub(crate) fn other_elements(element: &XmlElement, known: &[&str]) -> Vec<XmlElement> {
+    element
+        .elements()
+        .filter(|child| !known.contains(&child.name.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Does `element` only have children named in `known`
+pub(crate) fn has_only_children(element: &XmlElement, known: &[&str]) -> bool {
+    element
+        .elements()
+        .all(|child| known.contains(&child.name.as_str()))
+}
+
+/// Text of the child `name` of `element`, if it's present and not empty
+pub(crate) fn child_text(element: &XmlElement, name: &str) -> Option<String> {
+    element
+        .child(name)
+        .map(XmlElement::text)
+        .filter(|text| !text.is_empty())
+}
+
+/// Value of the child `name` of `element`, parsed from its text
+pub(crate) fn child_value<T: FromStr>(element: &XmlElement, name: &str) -> Result<Option<T>> {
+    child_text(element, name)
+        .map(|text| {
+            text.trim().parse().map_err(|_| {
+                Error::InvalidXml(format!("invalid value '{}' for <{}>", text, name).into())
+            })
+        })
+        .transpose()
+}
+
+/// Element `name` with the text of `value`, if present
+pub(crate) fn optional_child(name: &str, value: &Option<String>) -> Option<XmlElement> {
+    value
+        .as_ref()
+        .map(|value| XmlElement::with_text(name, value))
+}
+
+/// Element `name` with the text of `value`, or with an empty text if it's `None` but the
+/// element was in `order`
+pub(crate) fn optional_text_child(
+    name: &str,
+    value: &Option<String>,
+    order: &[String],
+) -> Option<XmlElement> {
+    match value {
+        Some(value) => Some(XmlElement::with_text(name, value)),
+        None if order.iter().any(|known| known == name) => {
+            let mut element = XmlElement::new(name);
+            element.children.push(XmlNode::Text(String::new()));
+            Some(element)
+        }
+        None => None,
+    }
+}
+
+/// Names of the children of `element`, in order
+pub(crate) fn element_order(element: &XmlElement) -> Vec<String> {
+    element.elements().map(|child| child.name.clone()).collect()
+}
+
+/// Sort `elements` in the order of their names in `order`, elements not in `order` going last
+pub(crate) fn in_order(mut elements: Vec<XmlElement>, order: &[String]) -> Vec<XmlElement> {
+    elements.sort_by_key(|element| {
+        order
+            .iter()
+            .position(|name| *name == element.name)
+            .unwrap_or(order.len())
+    });
+    elements
+}
+
+/// Value of the `plugin` attribute of `element`, naming the plugin that saved it
+pub(crate) fn plugin(element: &XmlElement) -> Option<String> {
+    element.attribute("plugin").map(ToString::to_string)
+}
+
+/// Does `element` only have attributes named in `known`
+pub(crate) fn has_only_attributes(element: &XmlElement, known: &[&str]) -> bool {
+    element
+        .attributes
+        .iter()
+        .all(|(name, _)| known.contains(&name.as_str()))
+}
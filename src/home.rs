@@ -2,7 +2,9 @@
 
 use serde::Deserialize;
 
-use crate::client_internals::{Path, Result};
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
+use crate::client_internals::{JsonResponse, Path, Result};
 use crate::job::ShortJob;
 use crate::view::ShortView;
 use crate::Jenkins;
@@ -147,6 +149,22 @@ pub struct Home {
 impl Jenkins {
     /// Get Jenkins `Home`
     pub fn get_home(&self) -> Result<Home> {
-        Ok(self.get(&Path::Home)?.json()?)
+        let home: Home = self.get(&Path::Home)?.json_body()?;
+        if !home.use_crumbs {
+            self.crumb.disable();
+        }
+        Ok(home)
+    }
+}
+
+#[cfg(feature = "async")]
+impl crate::AsyncJenkins {
+    /// Get Jenkins `Home`
+    pub async fn get_home(&self) -> Result<Home> {
+        let home: Home = self.get(&Path::Home).await?.json_body().await?;
+        if !home.use_crumbs {
+            self.crumb.disable();
+        }
+        Ok(home)
     }
 }
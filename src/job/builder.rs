@@ -1,24 +1,38 @@
 //! Helper to build a job
 
-use reqwest::header::LOCATION;
+use std::time::Duration;
+
+use reqwest::blocking;
+use reqwest::header::{HeaderMap, LOCATION};
 
 use serde::{self, Serialize};
+use url::form_urlencoded;
 
+use crate::build::ShortBuild;
 use crate::client::{self, Result};
+use crate::client_internals::path::split_full_name;
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
+use crate::client_internals::JsonResponse;
 use crate::client_internals::{Name, Path};
+use crate::job::parameters::{resolve_parameters, FileParameter, JobParameterDefinitions};
 use crate::job::{Job, JobName};
 use crate::queue::ShortQueueItem;
+#[cfg(feature = "async")]
+use crate::AsyncJenkins;
 use crate::Jenkins;
 
 /// Helper to build a job
 #[derive(Debug)]
-pub struct JobBuilder<'a, 'b, 'c, 'd> {
+pub struct JobBuilder<'a, 'b, 'c, 'd, C = Jenkins> {
+    folders: Vec<Name<'a>>,
     job_name: Name<'a>,
-    jenkins_client: &'b Jenkins,
+    jenkins_client: &'b C,
     delay: Option<u32>,
     cause: Option<&'c str>,
     token: Option<&'d str>,
-    parameters: Option<String>,
+    parameters: Option<Vec<(String, String)>>,
+    files: Vec<(String, FileParameter)>,
 }
 
 impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
@@ -27,146 +41,277 @@ impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
     where
         T: Job,
     {
-        let path = jenkins_client.url_to_path(&job.url());
+        let (folders, path) = jenkins_client
+            .url_to_path(job.url())?
+            .into_folders_and_leaf();
         if let Path::Job {
             name,
             configuration: None,
         } = path
         {
             return Ok(JobBuilder {
+                folders,
                 job_name: name,
                 jenkins_client,
                 delay: None,
                 cause: None,
                 token: None,
                 parameters: None,
+                files: vec![],
             });
-        } else if let Path::InFolder {
-            folder_name: _folder_name,
-            path: sub_path,
-        } = path
-        {
-            if let Path::Job {
-                name,
-                configuration: None,
-            } = sub_path.as_ref()
-            {
-                return Ok(JobBuilder {
-                    job_name: name.clone(),
-                    jenkins_client,
-                    delay: None,
-                    cause: None,
-                    token: None,
-                    parameters: None,
-                });
-            }
         }
         Err(client::Error::InvalidUrl {
             url: job.url().to_string(),
             expected: client::error::ExpectedType::Job,
+        })
+    }
+
+    /// Trigger the build
+    pub fn send(mut self) -> Result<ShortQueueItem> {
+        let jenkins_client = self.jenkins_client;
+        if self.checks_parameters() {
+            let definitions: JobParameterDefinitions = jenkins_client
+                .get_with_params(&self.job_path(), JobParameterDefinitions::query())?
+                .json_body()?;
+            self.resolve_parameters(definitions)?;
         }
-        .into())
+        let response = match self.into_request() {
+            BuildRequest::Get { path, qps } => jenkins_client.trigger_with_params(&path, &qps)?,
+            BuildRequest::Post { path, body, qps } => {
+                jenkins_client.post_with_body(&path, body, &borrow_qps(&qps))?
+            }
+            BuildRequest::Multipart {
+                path,
+                parameters,
+                files,
+                qps,
+            } => {
+                let form = parameters
+                    .into_iter()
+                    .fold(blocking::multipart::Form::new(), |form, (name, value)| {
+                        form.text(name, value)
+                    });
+                let form = files.into_iter().fold(form, |form, (name, file)| {
+                    form.part(
+                        name,
+                        blocking::multipart::Part::bytes(file.content).file_name(file.file_name),
+                    )
+                });
+                jenkins_client.post_multipart(&path, form, &borrow_qps(&qps))?
+            }
+        };
+        queue_item_from_headers(response.headers())
+    }
+
+    /// Trigger the build and wait for it to leave the queue, returning the `ShortBuild` once it
+    /// has started
+    ///
+    /// # Errors
+    /// If the queue item is cancelled, this method will return an
+    /// [`Error::QueueItemCancelled`](../../enum.Error.html#variant.QueueItemCancelled)
+    ///
+    /// If the build hasn't started after `timeout`, this method will return an
+    /// [`Error::Timeout`](../../enum.Error.html#variant.Timeout)
+    pub fn send_and_wait_started(self, timeout: Duration) -> Result<ShortBuild> {
+        let jenkins_client = self.jenkins_client;
+        let job_path = self.job_path();
+        self.send()?
+            .wait_started(jenkins_client, &job_path, timeout)
     }
+}
 
+#[cfg(feature = "async")]
+impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd, AsyncJenkins> {
+    /// Trigger the build
+    pub async fn send(mut self) -> Result<ShortQueueItem> {
+        let jenkins_client = self.jenkins_client;
+        if self.checks_parameters() {
+            let definitions: JobParameterDefinitions = jenkins_client
+                .get_with_params(&self.job_path(), JobParameterDefinitions::query())
+                .await?
+                .json_body()
+                .await?;
+            self.resolve_parameters(definitions)?;
+        }
+        let response = match self.into_request() {
+            BuildRequest::Get { path, qps } => jenkins_client.get_with_params(&path, &qps).await?,
+            BuildRequest::Post { path, body, qps } => {
+                jenkins_client
+                    .post_with_body(&path, body, &borrow_qps(&qps))
+                    .await?
+            }
+            BuildRequest::Multipart {
+                path,
+                parameters,
+                files,
+                qps,
+            } => {
+                let form = parameters
+                    .into_iter()
+                    .fold(reqwest::multipart::Form::new(), |form, (name, value)| {
+                        form.text(name, value)
+                    });
+                let form = files.into_iter().fold(form, |form, (name, file)| {
+                    form.part(
+                        name,
+                        reqwest::multipart::Part::bytes(file.content).file_name(file.file_name),
+                    )
+                });
+                jenkins_client
+                    .post_multipart(&path, form, &borrow_qps(&qps))
+                    .await?
+            }
+        };
+        queue_item_from_headers(response.headers())
+    }
+}
+
+impl<'a, 'b, 'c, 'd, C> JobBuilder<'a, 'b, 'c, 'd, C> {
     pub(crate) fn new_from_job_name<J>(
         name: J,
-        jenkins_client: &'b Jenkins,
+        jenkins_client: &'b C,
         name_encoded: bool,
     ) -> Result<Self>
     where
         J: Into<JobName<'a>>,
     {
-        let job_name = if !name_encoded {
-            Name::Name(name.into().0)
-        } else {
-            Name::UrlEncodedName(name.into().0)
-        };
+        let (folders, job_name) = split_full_name(name.into().0, name_encoded);
         Ok(JobBuilder {
+            folders,
             job_name,
             jenkins_client,
             delay: None,
             cause: None,
             token: None,
             parameters: None,
+            files: vec![],
         })
     }
 
-    /// Trigger the build
-    pub fn send(self) -> Result<ShortQueueItem> {
-        let response = match (self.token, self.parameters) {
-            (Some(token), None) => {
-                let bound_cause = self.cause.unwrap_or("");
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                qps.push(("token", &token));
-                if self.cause.is_some() {
-                    qps.push(("cause", &bound_cause));
-                }
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
-                }
+    /// Are the parameters checked against the definitions of the job before sending the build
+    ///
+    /// A build triggered remotely with a token may not be allowed to read the job, so its
+    /// parameters are sent as they are.
+    fn checks_parameters(&self) -> bool {
+        self.token.is_none() && (self.parameters.is_some() || !self.files.is_empty())
+    }
+
+    /// Check the parameters against the definitions of the job, and add the default values
+    fn resolve_parameters(&mut self, definitions: JobParameterDefinitions) -> Result<()> {
+        let files = self
+            .files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        self.parameters = Some(resolve_parameters(
+            &definitions.into_definitions(),
+            self.parameters.take().unwrap_or_default(),
+            &files,
+        )?);
+        Ok(())
+    }
+
+    /// Path to the job to build
+    fn job_path(&self) -> Path<'a> {
+        Path::in_folders(
+            self.folders.clone(),
+            Path::Job {
+                name: self.job_name.clone(),
+                configuration: None,
+            },
+        )
+    }
 
-                self.jenkins_client.get_with_params(
-                    &Path::BuildJob {
+    fn into_request(self) -> BuildRequest<'a> {
+        let mut qps: Vec<(&str, String)> = Vec::new();
+        if !self.files.is_empty() {
+            if let Some(token) = self.token {
+                qps.push(("token", token.to_string()));
+            }
+            if let Some(delay) = self.delay {
+                qps.push(("delay", delay.to_string()));
+            }
+            return BuildRequest::Multipart {
+                path: Path::in_folders(
+                    self.folders,
+                    Path::BuildJobWithParameters {
                         name: self.job_name,
                     },
-                    &qps,
-                )?
+                ),
+                parameters: self.parameters.unwrap_or_default(),
+                files: self.files,
+                qps,
+            };
+        }
+        match (self.token, self.parameters) {
+            (Some(token), None) => {
+                qps.push(("token", token.to_string()));
+                if let Some(cause) = self.cause {
+                    qps.push(("cause", cause.to_string()));
+                }
+                if let Some(delay) = self.delay {
+                    qps.push(("delay", delay.to_string()));
+                }
+                BuildRequest::Get {
+                    path: Path::in_folders(
+                        self.folders,
+                        Path::BuildJob {
+                            name: self.job_name,
+                        },
+                    ),
+                    qps,
+                }
             }
             (Some(token), Some(parameters)) => {
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
+                if let Some(delay) = self.delay {
+                    qps.push(("delay", delay.to_string()));
+                }
+                BuildRequest::Post {
+                    path: Path::in_folders(
+                        self.folders,
+                        Path::BuildJobWithParameters {
+                            name: self.job_name,
+                        },
+                    ),
+                    body: form_urlencoded::Serializer::new(String::new())
+                        .append_pair("token", token)
+                        .extend_pairs(parameters)
+                        .finish(),
+                    qps,
                 }
-                self.jenkins_client.post_with_body(
-                    &Path::BuildJobWithParameters {
-                        name: self.job_name,
-                    },
-                    format!("token={}&{}", token, parameters),
-                    &qps,
-                )?
             }
             (None, None) => {
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
+                if let Some(delay) = self.delay {
+                    qps.push(("delay", delay.to_string()));
+                }
+                BuildRequest::Post {
+                    path: Path::in_folders(
+                        self.folders,
+                        Path::BuildJob {
+                            name: self.job_name,
+                        },
+                    ),
+                    body: "".to_string(),
+                    qps,
                 }
-                self.jenkins_client.post_with_body(
-                    &Path::BuildJob {
-                        name: self.job_name,
-                    },
-                    "",
-                    &qps,
-                )?
             }
             (None, Some(parameters)) => {
-                let bound_delay = format!("{}", self.delay.unwrap_or(0));
-                let mut qps: Vec<(&str, &str)> = Vec::new();
-                if self.delay.is_some() {
-                    qps.push(("delay", &bound_delay));
+                if let Some(delay) = self.delay {
+                    qps.push(("delay", delay.to_string()));
+                }
+                BuildRequest::Post {
+                    path: Path::in_folders(
+                        self.folders,
+                        Path::BuildJobWithParameters {
+                            name: self.job_name,
+                        },
+                    ),
+                    body: form_urlencoded::Serializer::new(String::new())
+                        .extend_pairs(parameters)
+                        .finish(),
+                    qps,
                 }
-                self.jenkins_client.post_with_body(
-                    &Path::BuildJobWithParameters {
-                        name: self.job_name,
-                    },
-                    parameters,
-                    &qps,
-                )?
-            }
-        };
-        if let Some(location) = response.headers().get(LOCATION) {
-            Ok(ShortQueueItem {
-                url: location.to_str().unwrap().to_string(),
-                extra_fields: None,
-            })
-        } else {
-            Err(client::Error::InvalidUrl {
-                url: "".to_string(),
-                expected: client::error::ExpectedType::QueueItem,
             }
-            .into())
         }
     }
 
@@ -187,24 +332,101 @@ impl<'a, 'b, 'c, 'd> JobBuilder<'a, 'b, 'c, 'd> {
         Ok(self)
     }
 
-    /// Build with parameters
+    /// Build with parameters, added to the parameters already set
+    ///
+    /// Supported parameters type: Boolean, Choice, Multi-line string, Password, Run, String,
+    /// Credentials (with the id of the credentials). Parameters of other types accepting several
+    /// values, like extended choice parameters, are set by repeating their name, like
+    /// `&[("targets", "linux"), ("targets", "windows")]`.
     ///
-    /// Supported parameters type: Boolean, Choice, Multi-line string, Password, Run, String
+    /// File parameters are set with
+    /// [`with_file_parameter`](#method.with_file_parameter)
     ///
-    /// Unsupported parameters type: File, Credentials
+    /// When sending this build, the parameters are checked against the
+    /// [`ParameterDefinition`](../parameters/struct.ParameterDefinition.html)s of the `Job`,
+    /// and parameters left out are sent with their default value. They aren't checked for a
+    /// build triggered with
+    /// [`remotely_with_token_and_cause`](#method.remotely_with_token_and_cause).
     /// # Errors
-    /// If used on a `Job` without parameters, sending this build will return an
-    /// [`Error::IllegalState`](../enum.Error.html#variant.IllegalState)
+    /// If a parameter is not defined by the `Job`, sending this build will return an
+    /// [`Error::UnknownParameter`](../../enum.Error.html#variant.UnknownParameter)
     ///
-    /// If used with invalid parameters type / value, sending this build will return an
-    /// [`Error::IllegalArgument`](../enum.Error.html#variant.IllegalArgument)
+    /// If a value is not accepted by its parameter, or a parameter of a supported type is set
+    /// several times, sending this build will return an
+    /// [`Error::InvalidParameterValue`](../../enum.Error.html#variant.InvalidParameterValue)
     ///
     /// This methods will return an error if serializing `parameters` fails.
-    pub fn with_parameters<T: Serialize>(mut self, parameters: &T) -> Result<Self> {
+    pub fn with_parameters<T: Serialize>(self, parameters: &T) -> Result<Self> {
+        let parameters =
+            serde_urlencoded::to_string(parameters).map_err(client::Error::InvalidParameters)?;
+        form_urlencoded::parse(parameters.as_bytes())
+            .into_owned()
+            .try_fold(self, |builder, (name, value)| {
+                builder.with_parameter(&name, &value)
+            })
+    }
+
+    /// Build with the parameter `name` set to `value`. Calling it again with the same `name`
+    /// adds a value to a parameter accepting several values
+    ///
+    /// See [`with_parameters`](#method.with_parameters)
+    pub fn with_parameter(mut self, name: &str, value: &str) -> Result<Self> {
+        if self.token.is_some() {
+            return Err(client::Error::UnsupportedBuildConfiguration);
+        }
+        self.parameters
+            .get_or_insert_with(Vec::new)
+            .push((name.to_string(), value.to_string()));
+        Ok(self)
+    }
+
+    /// Build with the file parameter `name` set to `file`, uploaded with the build request
+    ///
+    /// See [`with_parameters`](#method.with_parameters)
+    pub fn with_file_parameter(mut self, name: &str, file: FileParameter) -> Result<Self> {
         if self.token.is_some() {
-            return Err(client::Error::UnsupportedBuildConfiguration.into());
+            return Err(client::Error::UnsupportedBuildConfiguration);
         }
-        self.parameters = Some(serde_urlencoded::to_string(parameters)?);
+        self.files.push((name.to_string(), file));
         Ok(self)
     }
 }
+
+/// Request to send to Jenkins to trigger a build
+enum BuildRequest<'a> {
+    Get {
+        path: Path<'a>,
+        qps: Vec<(&'static str, String)>,
+    },
+    Post {
+        path: Path<'a>,
+        body: String,
+        qps: Vec<(&'static str, String)>,
+    },
+    Multipart {
+        path: Path<'a>,
+        parameters: Vec<(String, String)>,
+        files: Vec<(String, FileParameter)>,
+        qps: Vec<(&'static str, String)>,
+    },
+}
+
+fn borrow_qps<'a>(qps: &'a [(&'static str, String)]) -> Vec<(&'a str, &'a str)> {
+    qps.iter()
+        .map(|(key, value)| (*key, value.as_str()))
+        .collect()
+}
+
+fn queue_item_from_headers(headers: &HeaderMap) -> Result<ShortQueueItem> {
+    if let Some(location) = headers.get(LOCATION).and_then(|v| v.to_str().ok()) {
+        Ok(ShortQueueItem {
+            url: location.to_string(),
+            extra_fields: None,
+        })
+    } else {
+        Err(client::Error::InvalidUrl {
+            url: "".to_string(),
+            expected: client::error::ExpectedType::QueueItem,
+        })
+    }
+}
@@ -4,11 +4,15 @@ use serde::{self, Deserialize, Serialize};
 
 use crate::helpers::Class;
 
-use super::JobBuilder;
+use super::parameters::{JobParameterDefinitions, ParameterDefinition};
+use super::{BuildHistory, JobBuilder};
 use crate::action::CommonAction;
 use crate::build::{CommonBuild, ShortBuild};
 use crate::client::{self, Result};
-use crate::client_internals::{Name, Path};
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
+use crate::client_internals::{JsonResponse, Name, Path};
+use crate::config::JobConfig;
 use crate::queue::ShortQueueItem;
 use crate::view::ViewName;
 use crate::Jenkins;
@@ -92,19 +96,27 @@ where
 {
     /// Get the full details of a `Job` matching the `ShortJob`
     pub fn get_full_job(&self, jenkins_client: &Jenkins) -> Result<T> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::Job { .. } = path {
-            return Ok(jenkins_client.get(&path)?.json()?);
-        } else if let Path::InFolder { path: sub_path, .. } = &path {
-            if let Path::Job { .. } = sub_path.as_ref() {
-                return Ok(jenkins_client.get(&path)?.json()?);
-            }
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::Job { .. } = path.leaf() {
+            return jenkins_client.get(&path)?.json_body();
         }
         Err(client::Error::InvalidUrl {
             url: self.url.clone(),
             expected: client::error::ExpectedType::Job,
+        })
+    }
+
+    /// Get the full details of a `Job` matching the `ShortJob` with an asynchronous client
+    #[cfg(feature = "async")]
+    pub async fn get_full_job_async(&self, jenkins_client: &crate::AsyncJenkins) -> Result<T> {
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::Job { .. } = path.leaf() {
+            return jenkins_client.get(&path).await?.json_body().await;
         }
-        .into())
+        Err(client::Error::InvalidUrl {
+            url: self.url.clone(),
+            expected: client::error::ExpectedType::Job,
+        })
     }
 }
 
@@ -141,39 +153,45 @@ pub trait Job {
 
     /// Enable a `Job`. It may need to be refreshed as it may have been updated
     fn enable(&self, jenkins_client: &Jenkins) -> Result<()> {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Job {
-            name,
-            configuration: None,
-        } = path
-        {
-            let _ = jenkins_client.post(&Path::JobEnable { name })?;
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::JobEnable { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post(&path)?;
             Ok(())
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url().to_string(),
                 expected: client::error::ExpectedType::Job,
-            }
-            .into())
+            })
         }
     }
 
     /// Disable a `Job`. It may need to be refreshed as it may have been updated
     fn disable(&self, jenkins_client: &Jenkins) -> Result<()> {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Job {
-            name,
-            configuration: None,
-        } = path
-        {
-            let _ = jenkins_client.post(&Path::JobDisable { name })?;
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::JobDisable { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post(&path)?;
             Ok(())
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url().to_string(),
                 expected: client::error::ExpectedType::Job,
-            }
-            .into())
+            })
         }
     }
 
@@ -182,14 +200,10 @@ pub trait Job {
     where
         V: Into<ViewName<'a>>,
     {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Job {
-            name,
-            configuration: None,
-        } = path
-        {
+        let path = jenkins_client.url_to_path(self.url())?;
+        if let Some(full_name) = path.full_job_name() {
             let _ = jenkins_client.post(&Path::AddJobToView {
-                job_name: name,
+                job_name: Name::UrlEncodedName(&full_name),
                 view_name: Name::Name(view_name.into().0),
             })?;
             Ok(())
@@ -197,8 +211,7 @@ pub trait Job {
             Err(client::Error::InvalidUrl {
                 url: self.url().to_string(),
                 expected: client::error::ExpectedType::Job,
-            }
-            .into())
+            })
         }
     }
 
@@ -207,14 +220,10 @@ pub trait Job {
     where
         V: Into<ViewName<'a>>,
     {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Job {
-            name,
-            configuration: None,
-        } = path
-        {
+        let path = jenkins_client.url_to_path(self.url())?;
+        if let Some(full_name) = path.full_job_name() {
             let _ = jenkins_client.post(&Path::RemoveJobFromView {
-                job_name: name,
+                job_name: Name::UrlEncodedName(&full_name),
                 view_name: Name::Name(view_name.into().0),
             })?;
             Ok(())
@@ -222,41 +231,171 @@ pub trait Job {
             Err(client::Error::InvalidUrl {
                 url: self.url().to_string(),
                 expected: client::error::ExpectedType::Job,
-            }
-            .into())
+            })
         }
     }
 
     /// Get the config.xml file for this job
     fn get_config_xml(&self, jenkins_client: &Jenkins) -> Result<String> {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Job { name, .. } = path {
-            return Ok(jenkins_client
-                .get(&Path::ConfigXML {
-                    job_name: name,
-                    folder_name: None,
-                })?
-                .text()?);
-        } else if let Path::InFolder {
-            path: sub_path,
-            folder_name,
-        } = &path
-        {
-            if let Path::Job { name, .. } = sub_path.as_ref() {
-                return Ok(jenkins_client
-                    .get(&Path::ConfigXML {
-                        job_name: name.clone(),
-                        folder_name: Some(folder_name.clone()),
-                    })?
-                    .text()?);
-            }
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job { name, .. } => Some(Path::ConfigXML { job_name: name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            return Ok(jenkins_client.get(&path)?.text()?);
         }
 
         Err(client::Error::InvalidUrl {
             url: self.url().to_string(),
             expected: client::error::ExpectedType::Build,
+        })
+    }
+
+    /// Get the configuration of this job, parsed from its config.xml file
+    fn get_config<C: JobConfig>(&self, jenkins_client: &Jenkins) -> Result<C> {
+        C::from_xml(&self.get_config_xml(jenkins_client)?)
+    }
+
+    /// Replace the configuration of this job. It may need to be refreshed as it may have been
+    /// updated
+    fn update_config<C: JobConfig>(&self, jenkins_client: &Jenkins, config: &C) -> Result<()> {
+        self.update_config_xml(jenkins_client, &config.to_xml()?)
+    }
+
+    /// Replace the config.xml file of this job. It may need to be refreshed as it may have been
+    /// updated
+    fn update_config_xml(&self, jenkins_client: &Jenkins, config_xml: &str) -> Result<()> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::ConfigXML { job_name: name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post_xml(&path, config_xml, &[])?;
+            Ok(())
+        } else {
+            Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })
+        }
+    }
+
+    /// Get the definitions of the parameters of this job
+    fn get_parameter_definitions(
+        &self,
+        jenkins_client: &Jenkins,
+    ) -> Result<Vec<ParameterDefinition>> {
+        let path = jenkins_client.url_to_path(self.url())?;
+        if let Path::Job {
+            configuration: None,
+            ..
+        } = path.leaf()
+        {
+            let definitions: JobParameterDefinitions = jenkins_client
+                .get_with_params(&path, JobParameterDefinitions::query())?
+                .json_body()?;
+            return Ok(definitions.into_definitions());
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url().to_string(),
+            expected: client::error::ExpectedType::Job,
+        })
+    }
+
+    /// Get the last `count` builds of this job, with a single request
+    fn build_history(&self, jenkins_client: &Jenkins, count: usize) -> Result<BuildHistory> {
+        let path = jenkins_client.url_to_path(self.url())?;
+        if let Path::Job { .. } = path.leaf() {
+            return jenkins_client
+                .get_with_params(&path, BuildHistory::query(count))?
+                .json_body();
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url().to_string(),
+            expected: client::error::ExpectedType::Job,
+        })
+    }
+
+    /// Rename this job to `new_name`, in the same folder
+    ///
+    /// # Errors
+    /// If a job named `new_name` already exists, this method will return an
+    /// [`Error::NameAlreadyUsed`](../enum.Error.html#variant.NameAlreadyUsed)
+    ///
+    /// If `new_name` is not a valid name, this method will return an
+    /// [`Error::InvalidName`](../enum.Error.html#variant.InvalidName)
+    fn rename(&self, jenkins_client: &Jenkins, new_name: &str) -> Result<()> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::JobRename { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post_with_body(&path, "", &[("newName", new_name)])?;
+            Ok(())
+        } else {
+            Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })
+        }
+    }
+
+    /// Delete this job
+    fn delete(&self, jenkins_client: &Jenkins) -> Result<()> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::JobDelete { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post(&path)?;
+            Ok(())
+        } else {
+            Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })
+        }
+    }
+
+    /// Move this job to the folder `destination`, which is a full name like `folder/subfolder`.
+    /// An empty `destination` moves the job to the root of Jenkins
+    fn move_to(&self, jenkins_client: &Jenkins, destination: &str) -> Result<()> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::JobMove { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let destination = format!("/{}", destination.trim_matches('/'));
+            let _ = jenkins_client.post_with_body(&path, "", &[("destination", &destination)])?;
+            Ok(())
+        } else {
+            Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })
         }
-        .into())
     }
 }
 
@@ -430,18 +569,18 @@ macro_rules! job_buildable_with_common_fields_and_impl {
                 pub health_report: Vec<HealthReport>,
                 /// Queue item of this job if it's waiting
                 pub queue_item: Option<ShortQueueItem>,
+                /// Properties of the job
+                pub property: Vec<CommonProperty>,
                 $(
                     $(#[$field_attr])*
                     pub $field: $field_type,
                 )*
-                $($(
-                    $(#[$private_field_attr])*
-                    $private_field: $private_field_type,
-                )*)*
-                private_fields {
-                    /// Properties of the job
-                    property: Vec<CommonProperty>,
-                }
+                $(private_fields {
+                    $(
+                        $(#[$private_field_attr])*
+                        $private_field: $private_field_type
+                    ),*
+                })*
             }
         }
     };
@@ -491,20 +630,71 @@ pub trait BuildableJob: Job + Sized {
 pub trait SCMPollable: Job + Sized {
     /// Poll configured SCM for changes
     fn poll_scm(&self, jenkins_client: &Jenkins) -> Result<()> {
-        let path = jenkins_client.url_to_path(&self.url());
-        if let Path::Job {
-            name,
-            configuration: None,
-        } = path
-        {
-            let _ = jenkins_client.post(&Path::PollSCMJob { name })?;
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::PollSCMJob { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post(&path)?;
             Ok(())
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url().to_string(),
                 expected: client::error::ExpectedType::Job,
-            }
-            .into())
+            })
+        }
+    }
+}
+
+/// Common trait for folders computing their jobs from a SCM, like multibranch projects and
+/// organization folders
+pub trait ComputedFolder: Job + Sized {
+    /// Scan the SCM to update the jobs of the folder, which is the branch indexing of a
+    /// multibranch project or the scan of an organization folder
+    fn scan(&self, jenkins_client: &Jenkins) -> Result<()> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::BuildJob { name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post_with_body(&path, "", &[("delay", "0")])?;
+            Ok(())
+        } else {
+            Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })
+        }
+    }
+
+    /// Get the log of the last scan of the folder
+    fn get_scan_log(&self, jenkins_client: &Jenkins) -> Result<String> {
+        let path = jenkins_client
+            .url_to_path(self.url())?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::ComputationLog { job_name: name }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            Ok(jenkins_client.get(&path)?.text()?)
+        } else {
+            Err(client::Error::InvalidUrl {
+                url: self.url().to_string(),
+                expected: client::error::ExpectedType::Job,
+            })
         }
     }
 }
@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::build::BuildStatus;
+use crate::client::{AdvancedQuery, TreeBuilder};
+use crate::client_internals::InternalAdvancedQueryParams;
+
+/// A build in the history of a job
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRecord {
+    /// Build number
+    pub number: u32,
+    /// Build result, `None` while the build is running
+    pub result: Option<BuildStatus>,
+    /// Duration in milliseconds
+    pub duration: u64,
+    /// Timestamp of the build start, in milliseconds
+    pub timestamp: u64,
+    /// Is this build currently running
+    #[serde(default)]
+    pub building: bool,
+}
+
+impl BuildRecord {
+    fn end(&self) -> u64 {
+        self.timestamp + self.duration
+    }
+}
+
+/// The last builds of a job, from the most recent one
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildHistory {
+    /// Builds of the job
+    #[serde(default, rename = "allBuilds")]
+    pub builds: Vec<BuildRecord>,
+}
+
+impl BuildHistory {
+    /// Query reading the last `count` builds of a job
+    pub(crate) fn query(count: usize) -> InternalAdvancedQueryParams {
+        InternalAdvancedQueryParams::from(AdvancedQuery::Tree(
+            TreeBuilder::new()
+                .with_field(
+                    TreeBuilder::object("allBuilds")
+                        .with_subfield("number")
+                        .with_subfield("result")
+                        .with_subfield("duration")
+                        .with_subfield("timestamp")
+                        .with_subfield("building")
+                        .with_range(0..count),
+                )
+                .build(),
+        ))
+    }
+
+    /// Compute statistics over the finished builds of the history
+    pub fn statistics(&self) -> BuildStatistics {
+        // Oldest build first
+        let finished: Vec<(&BuildRecord, BuildStatus)> = self
+            .builds
+            .iter()
+            .rev()
+            .filter(|build| !build.building)
+            .filter_map(|build| build.result.map(|result| (build, result)))
+            .collect();
+
+        let count_of = |status: BuildStatus| {
+            finished
+                .iter()
+                .filter(|(_, result)| *result == status)
+                .count()
+        };
+        let rate_of = |status: BuildStatus| {
+            if finished.is_empty() {
+                0.0
+            } else {
+                count_of(status) as f64 / finished.len() as f64
+            }
+        };
+
+        let mut durations: Vec<u64> = finished.iter().map(|(build, _)| build.duration).collect();
+        durations.sort_unstable();
+
+        let mut recoveries = vec![];
+        let mut failing_since: Option<&BuildRecord> = None;
+        let mut streak = 0;
+        let mut longest_failure_streak = 0;
+        for (build, result) in &finished {
+            match result {
+                BuildStatus::Failure => {
+                    failing_since = failing_since.or(Some(build));
+                    streak += 1;
+                    longest_failure_streak = longest_failure_streak.max(streak);
+                }
+                BuildStatus::Success => {
+                    if let Some(first_failure) = failing_since.take() {
+                        recoveries.push(build.end().saturating_sub(first_failure.timestamp));
+                    }
+                    streak = 0;
+                }
+                _ => streak = 0,
+            }
+        }
+
+        // Aborted and not built builds don't tell if the job passes
+        let outcomes: Vec<bool> = finished
+            .iter()
+            .filter_map(|(_, result)| match result {
+                BuildStatus::Success => Some(true),
+                BuildStatus::Unstable | BuildStatus::Failure => Some(false),
+                BuildStatus::Aborted | BuildStatus::NotBuilt => None,
+            })
+            .collect();
+        let flips = outcomes
+            .windows(2)
+            .filter(|pair| pair[0] != pair[1])
+            .count();
+
+        BuildStatistics {
+            builds: finished.len(),
+            success_rate: rate_of(BuildStatus::Success),
+            unstable_rate: rate_of(BuildStatus::Unstable),
+            failure_rate: rate_of(BuildStatus::Failure),
+            mean_duration: mean(&durations),
+            median_duration: percentile(&durations, 50),
+            p95_duration: percentile(&durations, 95),
+            mean_time_to_recovery: mean(&recoveries),
+            longest_failure_streak,
+            flip_flop_rate: if outcomes.len() > 1 {
+                flips as f64 / (outcomes.len() - 1) as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Statistics over the finished builds of a `BuildHistory`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuildStatistics {
+    /// Number of finished builds
+    pub builds: usize,
+    /// Ratio of successful builds
+    pub success_rate: f64,
+    /// Ratio of unstable builds
+    pub unstable_rate: f64,
+    /// Ratio of failed builds
+    pub failure_rate: f64,
+    /// Mean duration of the builds
+    pub mean_duration: Option<Duration>,
+    /// Median duration of the builds
+    pub median_duration: Option<Duration>,
+    /// 95th percentile of the duration of the builds
+    pub p95_duration: Option<Duration>,
+    /// Mean time from the start of a failed build to the end of the next successful build
+    pub mean_time_to_recovery: Option<Duration>,
+    /// Highest number of failed builds in a row
+    pub longest_failure_streak: usize,
+    /// Ratio of builds passing when the previous one didn't, or the opposite, ignoring aborted
+    /// builds
+    pub flip_flop_rate: f64,
+}
+
+/// Mean of durations in milliseconds
+fn mean(values: &[u64]) -> Option<Duration> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(Duration::from_millis(
+        values.iter().sum::<u64>() / values.len() as u64,
+    ))
+}
+
+/// Nearest rank percentile of sorted durations in milliseconds
+fn percentile(sorted: &[u64], percentile: usize) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    Some(Duration::from_millis(sorted[rank - 1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(results: &[Option<BuildStatus>]) -> BuildHistory {
+        BuildHistory {
+            builds: results
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(index, result)| BuildRecord {
+                    number: index as u32 + 1,
+                    result: *result,
+                    duration: (index as u64 + 1) * 1000,
+                    timestamp: index as u64 * 10_000,
+                    building: result.is_none(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn can_compute_build_statistics() {
+        use BuildStatus::*;
+
+        let statistics = history(&[
+            Some(Success),
+            Some(Failure),
+            Some(Failure),
+            Some(Success),
+            Some(Unstable),
+            Some(Aborted),
+            Some(Success),
+            Some(Failure),
+            Some(Success),
+            Some(Success),
+            None,
+        ])
+        .statistics();
+
+        assert_eq!(statistics.builds, 10);
+        assert_eq!(statistics.success_rate, 0.5);
+        assert_eq!(statistics.unstable_rate, 0.1);
+        assert_eq!(statistics.failure_rate, 0.3);
+        assert_eq!(statistics.mean_duration, Some(Duration::from_millis(5500)));
+        assert_eq!(statistics.median_duration, Some(Duration::from_secs(5)));
+        assert_eq!(statistics.p95_duration, Some(Duration::from_secs(10)));
+        // Recovering from build 2 to build 4 takes 24s, and from build 8 to build 9 takes 19s
+        assert_eq!(
+            statistics.mean_time_to_recovery,
+            Some(Duration::from_millis(21_500))
+        );
+        assert_eq!(statistics.longest_failure_streak, 2);
+        // 6 changes between the 9 builds that weren't aborted
+        assert_eq!(statistics.flip_flop_rate, 6.0 / 8.0);
+    }
+
+    #[test]
+    fn can_compute_statistics_of_empty_history() {
+        let statistics = BuildHistory::default().statistics();
+
+        assert_eq!(statistics.builds, 0);
+        assert_eq!(statistics.success_rate, 0.0);
+        assert_eq!(statistics.mean_duration, None);
+        assert_eq!(statistics.mean_time_to_recovery, None);
+        assert_eq!(statistics.flip_flop_rate, 0.0);
+    }
+}
@@ -1,6 +1,10 @@
 //! Jenkins Jobs
 
-use crate::client_internals::{Name, Path, Result};
+use crate::client_internals::path::split_full_name;
+#[cfg(feature = "async")]
+use crate::client_internals::AsyncJsonResponse;
+use crate::client_internals::{JsonResponse, Path, Result};
+use crate::helpers::Class;
 use crate::queue::ShortQueueItem;
 use crate::Jenkins;
 
@@ -10,7 +14,8 @@ use self::builder::JobBuilder;
 #[macro_use]
 mod common;
 pub use self::common::{
-    BallColor, BuildableJob, CommonJob, HealthReport, Job, JobName, SCMPollable, ShortJob,
+    BallColor, BuildableJob, CommonJob, ComputedFolder, HealthReport, Job, JobName, SCMPollable,
+    ShortJob,
 };
 mod flow;
 pub use self::flow::BuildFlowJob;
@@ -24,29 +29,36 @@ mod maven;
 pub use self::maven::{MavenModule, MavenModuleSet};
 mod multijob;
 pub use self::multijob::MultiJobProject;
+mod history;
+pub use self::history::{BuildHistory, BuildRecord, BuildStatistics};
 mod external;
 pub use self::external::ExternalJob;
 mod folder;
 pub use self::folder::Folder;
 mod multibranch_pipeline;
-pub use self::multibranch_pipeline::WorkflowMultiBranchProject;
+pub use self::multibranch_pipeline::{BranchJob, WorkflowMultiBranchProject};
+mod organization_folder;
+pub use self::organization_folder::OrganizationFolder;
+pub mod parameters;
+pub use self::parameters::{FileParameter, ParameterDefinition, ParameterType};
+mod walk;
+pub use self::walk::{JobWalk, WalkedJob};
 
 impl Jenkins {
-    /// Get a `Job` from it's `job_name`
+    /// Get a `Job` from it's `job_name`, which can be a full name like `folder/job`
     pub fn get_job<'a, J>(&self, job_name: J) -> Result<CommonJob>
     where
         J: Into<JobName<'a>>,
     {
-        Ok(self
-            .get(&Path::Job {
-                name: Name::Name(job_name.into().0),
-                configuration: None,
-            })?
-            .json()?)
+        self.get(&Path::for_job(job_name.into().0, false, |name| Path::Job {
+            name,
+            configuration: None,
+        }))?
+        .json_body()
         // self.get_job_as(job_name, None)
     }
 
-    /// Build a `Job` from it's `job_name`
+    /// Build a `Job` from it's `job_name`, which can be a full name like `folder/job`
     pub fn build_job<'a, J>(&self, job_name: J, name_encoded: bool) -> Result<ShortQueueItem>
     where
         J: Into<JobName<'a>>,
@@ -54,7 +66,8 @@ impl Jenkins {
         JobBuilder::new_from_job_name(job_name.into().0, self, name_encoded)?.send()
     }
 
-    /// Create a `JobBuilder` to setup a build of a `Job` from it's `job_name`
+    /// Create a `JobBuilder` to setup a build of a `Job` from it's `job_name`, which can be a
+    /// full name like `folder/job`
     pub fn job_builder<'a, 'b, 'c, 'd>(
         &'b self,
         job_name: &'a str,
@@ -63,14 +76,130 @@ impl Jenkins {
         JobBuilder::new_from_job_name(job_name, self, name_encoded)
     }
 
-    /// Poll SCM of a `Job` from it's `job_name`
+    /// Poll SCM of a `Job` from it's `job_name`, which can be a full name like `folder/job`
     pub fn poll_scm_job<'a, J>(&self, job_name: J) -> Result<()>
     where
         J: Into<JobName<'a>>,
     {
-        let _ = self.post(&Path::PollSCMJob {
-            name: Name::Name(job_name.into().0),
-        })?;
+        let _ = self.post(&Path::for_job(job_name.into().0, false, |name| {
+            Path::PollSCMJob { name }
+        }))?;
         Ok(())
     }
+
+    /// Create a `Job` named `job_name` from its config.xml file. The name can be a full name
+    /// like `folder/job` to create it in an existing folder
+    ///
+    /// # Errors
+    /// If a job named `job_name` already exists, this method will return an
+    /// [`Error::NameAlreadyUsed`](../enum.Error.html#variant.NameAlreadyUsed)
+    ///
+    /// If `job_name` is not a valid name, this method will return an
+    /// [`Error::InvalidName`](../enum.Error.html#variant.InvalidName)
+    pub fn create_job<'a, J>(&self, job_name: J, config_xml: &str) -> Result<()>
+    where
+        J: Into<JobName<'a>>,
+    {
+        let job_name = job_name.into().0;
+        let (folders, _) = split_full_name(job_name, false);
+        let _ = self.post_xml(
+            &Path::in_folders(folders, Path::CreateItem),
+            config_xml,
+            &[("name", short_name(job_name))],
+        )?;
+        Ok(())
+    }
+
+    /// Create a `Job` named `to` as a copy of the `Job` named `from`. Both can be full names
+    /// like `folder/job`
+    ///
+    /// # Errors
+    /// If a job named `to` already exists, this method will return an
+    /// [`Error::NameAlreadyUsed`](../enum.Error.html#variant.NameAlreadyUsed)
+    ///
+    /// If `to` is not a valid name, this method will return an
+    /// [`Error::InvalidName`](../enum.Error.html#variant.InvalidName)
+    pub fn copy_job<'a, 'b, F, T>(&self, from: F, to: T) -> Result<()>
+    where
+        F: Into<JobName<'a>>,
+        T: Into<JobName<'b>>,
+    {
+        let to = to.into().0;
+        let (folders, _) = split_full_name(to, false);
+        let from = format!("/{}", from.into().0);
+        let _ = self.post_with_body(
+            &Path::in_folders(folders, Path::CreateItem),
+            "",
+            &[("name", short_name(to)), ("mode", "copy"), ("from", &from)],
+        )?;
+        Ok(())
+    }
+
+    /// Create an empty `Folder` named `folder_name`. The name can be a full name like
+    /// `parent/folder` to create it in an existing folder
+    ///
+    /// # Errors
+    /// If a job named `folder_name` already exists, this method will return an
+    /// [`Error::NameAlreadyUsed`](../enum.Error.html#variant.NameAlreadyUsed)
+    ///
+    /// If `folder_name` is not a valid name, this method will return an
+    /// [`Error::InvalidName`](../enum.Error.html#variant.InvalidName)
+    pub fn create_folder<'a, J>(&self, folder_name: J) -> Result<()>
+    where
+        J: Into<JobName<'a>>,
+    {
+        let folder_name = folder_name.into().0;
+        let (folders, _) = split_full_name(folder_name, false);
+        let _ = self.post_with_body(
+            &Path::in_folders(folders, Path::CreateItem),
+            "",
+            &[
+                ("name", short_name(folder_name)),
+                ("mode", <Folder as Class>::with_class()),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Name of a job without the folders containing it
+fn short_name(full_name: &str) -> &str {
+    full_name.rsplit('/').next().unwrap_or(full_name)
+}
+
+#[cfg(feature = "async")]
+impl crate::AsyncJenkins {
+    /// Get a `Job` from it's `job_name`, which can be a full name like `folder/job`
+    pub async fn get_job<'a, J>(&self, job_name: J) -> Result<CommonJob>
+    where
+        J: Into<JobName<'a>>,
+    {
+        self.get(&Path::for_job(job_name.into().0, false, |name| Path::Job {
+            name,
+            configuration: None,
+        }))
+        .await?
+        .json_body()
+        .await
+    }
+
+    /// Build a `Job` from it's `job_name`, which can be a full name like `folder/job`
+    pub async fn build_job<'a, J>(&self, job_name: J, name_encoded: bool) -> Result<ShortQueueItem>
+    where
+        J: Into<JobName<'a>>,
+    {
+        JobBuilder::new_from_job_name(job_name.into().0, self, name_encoded)?
+            .send()
+            .await
+    }
+
+    /// Create a `JobBuilder` to setup a build of a `Job` from it's `job_name`, which can be a
+    /// full name like `folder/job`
+    pub fn job_builder<'a, 'b, 'c, 'd>(
+        &'b self,
+        job_name: &'a str,
+        name_encoded: bool,
+    ) -> Result<JobBuilder<'a, 'b, 'c, 'd, Self>> {
+        JobBuilder::new_from_job_name(job_name, self, name_encoded)
+    }
 }
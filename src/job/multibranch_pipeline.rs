@@ -1,11 +1,17 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::helpers::Class;
 
-use super::Job;
-use crate::action::CommonAction;
+use super::{BallColor, CommonJob, ComputedFolder, Job};
+use crate::action::{
+    ChangeRequestAction, CommonAction, ContributorMetadataAction, ObjectMetadataAction,
+};
 use crate::build::{CommonBuild, ShortBuild};
+use crate::client::{self, AdvancedQuery, Result, TreeBuilder};
+use crate::client_internals::{InternalAdvancedQueryParams, JsonResponse, Name, Path};
 use crate::job::ShortJob;
+use crate::property::{BranchJobProperty, CommonProperty};
+use crate::Jenkins;
 
 job_base_with_common_fields_and_impl!(
     /// A pipeline project
@@ -18,4 +24,160 @@ job_base_with_common_fields_and_impl!(
 );
 register_class!("org.jenkinsci.plugins.workflow.multibranch.WorkflowMultiBranchProject" => WorkflowMultiBranchProject);
 
-impl WorkflowMultiBranchProject {}
+impl ComputedFolder for WorkflowMultiBranchProject {}
+
+impl WorkflowMultiBranchProject {
+    /// Get the jobs of the project with the metadata of the branch or change request they
+    /// build
+    pub fn get_branches(&self, jenkins_client: &Jenkins) -> Result<Vec<BranchJob>> {
+        #[derive(Deserialize)]
+        struct Branches {
+            jobs: Vec<BranchJob>,
+        }
+
+        let path = self.project_path(jenkins_client)?;
+        let tree = TreeBuilder::new()
+            .with_field(
+                TreeBuilder::object("jobs")
+                    .with_subfield("_class")
+                    .with_subfield("name")
+                    .with_subfield("displayName")
+                    .with_subfield("url")
+                    .with_subfield("color")
+                    .with_subfield(
+                        TreeBuilder::object("property")
+                            .with_subfield("_class")
+                            .with_subfield(TreeBuilder::object("branch").with_subfield("name")),
+                    )
+                    .with_subfield(
+                        TreeBuilder::object("actions")
+                            .with_subfield("_class")
+                            .with_subfield("id")
+                            .with_subfield("URL")
+                            .with_subfield("title")
+                            .with_subfield("author")
+                            .with_subfield("authorDisplayName")
+                            .with_subfield("authorEmail")
+                            .with_subfield("objectDisplayName")
+                            .with_subfield("objectDescription")
+                            .with_subfield("objectUrl")
+                            .with_subfield("contributor")
+                            .with_subfield("contributorDisplayName")
+                            .with_subfield("contributorEmail"),
+                    ),
+            )
+            .build();
+        let branches: Branches = jenkins_client
+            .get_with_params(
+                &path,
+                InternalAdvancedQueryParams::from(AdvancedQuery::Tree(tree)),
+            )?
+            .json_body()?;
+        Ok(branches.jobs)
+    }
+
+    /// Get the job of the branch named `branch_name`, like `feature/my-feature`
+    ///
+    /// Jenkins names branch jobs with the url encoded name of their branch, so the name is
+    /// encoded twice in the URL of the job.
+    pub fn get_branch_job(&self, jenkins_client: &Jenkins, branch_name: &str) -> Result<CommonJob> {
+        let job_name = urlencoding::encode(branch_name);
+        let (mut folders, leaf) = self.project_path(jenkins_client)?.into_folders_and_leaf();
+        if let Path::Job { name, .. } = leaf {
+            folders.push(name);
+        }
+        let path = Path::in_folders(
+            folders,
+            Path::Job {
+                name: Name::Name(&job_name),
+                configuration: None,
+            },
+        );
+        jenkins_client.get(&path)?.json_body()
+    }
+
+    fn project_path<'a>(&'a self, jenkins_client: &Jenkins) -> Result<Path<'a>> {
+        let path = jenkins_client.url_to_path(self.url())?;
+        if let Path::Job {
+            configuration: None,
+            ..
+        } = path.leaf()
+        {
+            return Ok(path);
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url().to_string(),
+            expected: client::error::ExpectedType::Job,
+        })
+    }
+}
+
+/// A job of a multibranch project, building a branch or a change request
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchJob {
+    /// _class of the job
+    #[serde(rename = "_class")]
+    pub class: Option<String>,
+    /// Name of the job, which is the url encoded name of the branch
+    pub name: String,
+    /// Display name of the job, which is the name of the branch
+    pub display_name: String,
+    /// URL for the job
+    pub url: String,
+    /// Ball Color for the status of the job
+    pub color: Option<BallColor>,
+    /// Properties of the job
+    #[serde(default)]
+    pub property: Vec<CommonProperty>,
+    /// Actions of the job
+    #[serde(default)]
+    pub actions: Vec<Option<CommonAction>>,
+}
+
+impl BranchJob {
+    /// Get the branch built by the job
+    pub fn branch(&self) -> Option<BranchJobProperty> {
+        self.property
+            .iter()
+            .find_map(|property| property.as_variant().ok())
+    }
+
+    /// Get the change request built by the job, if it builds one
+    pub fn change_request(&self) -> Option<ChangeRequestAction> {
+        self.find_action()
+    }
+
+    /// Get the metadata of the branch or change request in the SCM
+    pub fn metadata(&self) -> Option<ObjectMetadataAction> {
+        self.find_action()
+    }
+
+    /// Get the contributor of the change request built by the job
+    pub fn contributor(&self) -> Option<ContributorMetadataAction> {
+        self.find_action()
+    }
+
+    fn find_action<T>(&self) -> Option<T>
+    where
+        T: Class + crate::action::Action,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.actions
+            .iter()
+            .flatten()
+            .find_map(|action| action.as_variant().ok())
+    }
+
+    /// Get the full details of the job
+    pub fn get_full_job(&self, jenkins_client: &Jenkins) -> Result<CommonJob> {
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::Job { .. } = path.leaf() {
+            return jenkins_client.get(&path)?.json_body();
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url.clone(),
+            expected: client::error::ExpectedType::Job,
+        })
+    }
+}
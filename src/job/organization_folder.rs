@@ -0,0 +1,21 @@
+use serde::Deserialize;
+
+use crate::helpers::Class;
+
+use super::{ComputedFolder, Job};
+use crate::action::CommonAction;
+use crate::build::{CommonBuild, ShortBuild};
+use crate::job::ShortJob;
+
+job_base_with_common_fields_and_impl!(
+    /// An organization folder, with a multibranch project for each repository
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct OrganizationFolder {
+        /// List of the multibranch projects in the organization
+        pub jobs: Vec<ShortJob>,
+    }
+);
+register_class!("jenkins.branch.OrganizationFolder" => OrganizationFolder);
+
+impl ComputedFolder for OrganizationFolder {}
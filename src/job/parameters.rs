@@ -0,0 +1,460 @@
+//! Parameters defined by a `Job`
+
+use serde::{self, Deserialize};
+
+use crate::client::{self, AdvancedQuery, Result, TreeBuilder};
+use crate::client_internals::InternalAdvancedQueryParams;
+use crate::property::{CommonProperty, ParametersDefinitionProperty};
+
+/// Definition of a parameter of a `Job`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "RawParameterDefinition")]
+pub struct ParameterDefinition {
+    /// Name of the parameter
+    pub name: String,
+    /// Description of the parameter
+    pub description: Option<String>,
+    /// Type of the parameter
+    pub parameter_type: ParameterType,
+    /// Default value of the parameter, as it would be sent to Jenkins
+    pub default_value: Option<String>,
+}
+
+/// Type of a parameter, with the values it accepts
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterType {
+    /// `hudson.model.StringParameterDefinition`
+    String,
+    /// `hudson.model.TextParameterDefinition`, a multi-line string
+    Text,
+    /// `hudson.model.BooleanParameterDefinition`
+    Boolean,
+    /// `hudson.model.ChoiceParameterDefinition`
+    Choice {
+        /// Allowed values
+        choices: Vec<String>,
+    },
+    /// `hudson.model.PasswordParameterDefinition`, Jenkins doesn't provide its default value
+    Password,
+    /// `hudson.model.RunParameterDefinition`, a build of another job as `job#number`
+    Run {
+        /// Full name of the job
+        project_name: Option<String>,
+    },
+    /// `hudson.model.FileParameterDefinition`
+    File,
+    /// `com.cloudbees.plugins.credentials.CredentialsParameterDefinition`, the id of a
+    /// credentials
+    Credentials {
+        /// Type of credentials accepted
+        credential_type: Option<String>,
+        /// Is a value required
+        required: bool,
+    },
+    /// Another type of parameter, whose values are not checked
+    Other {
+        /// _class provided by Jenkins
+        class: Option<String>,
+    },
+}
+
+/// Content of a file parameter, uploaded when building a `Job`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileParameter {
+    /// Name of the file
+    pub file_name: String,
+    /// Content of the file
+    pub content: Vec<u8>,
+}
+
+impl FileParameter {
+    /// Read the file at `path`
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read(path).map_err(|source| client::Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(FileParameter {
+            file_name: path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            content,
+        })
+    }
+
+    /// A file named `file_name` containing `content`
+    pub fn from_bytes<N, C>(file_name: N, content: C) -> Self
+    where
+        N: Into<String>,
+        C: Into<Vec<u8>>,
+    {
+        FileParameter {
+            file_name: file_name.into(),
+            content: content.into(),
+        }
+    }
+}
+
+/// Parameter definition as provided by Jenkins
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RawParameterDefinition {
+    #[serde(rename = "_class")]
+    class: Option<String>,
+    name: String,
+    description: Option<String>,
+    default_parameter_value: Option<RawParameterValue>,
+    choices: Option<Vec<String>>,
+    project_name: Option<String>,
+    credential_type: Option<String>,
+    #[serde(default)]
+    required: bool,
+}
+
+/// Default value of a parameter as provided by Jenkins
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RawParameterValue {
+    value: Option<serde_json::Value>,
+    job_name: Option<String>,
+    number: Option<String>,
+}
+
+impl RawParameterValue {
+    fn into_value(self) -> Option<String> {
+        match (self.value, self.job_name, self.number) {
+            (_, Some(job_name), Some(number)) => Some(format!("{}#{}", job_name, number)),
+            (Some(serde_json::Value::String(value)), _, _) => Some(value),
+            (Some(serde_json::Value::Bool(value)), _, _) => Some(value.to_string()),
+            (Some(serde_json::Value::Number(value)), _, _) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl From<RawParameterDefinition> for ParameterDefinition {
+    fn from(raw: RawParameterDefinition) -> Self {
+        let parameter_type = match raw.class.as_deref() {
+            Some("hudson.model.StringParameterDefinition") => ParameterType::String,
+            Some("hudson.model.TextParameterDefinition") => ParameterType::Text,
+            Some("hudson.model.BooleanParameterDefinition") => ParameterType::Boolean,
+            Some("hudson.model.ChoiceParameterDefinition") => ParameterType::Choice {
+                choices: raw.choices.unwrap_or_default(),
+            },
+            Some("hudson.model.PasswordParameterDefinition") => ParameterType::Password,
+            Some("hudson.model.RunParameterDefinition") => ParameterType::Run {
+                project_name: raw.project_name,
+            },
+            Some("hudson.model.FileParameterDefinition") => ParameterType::File,
+            Some("com.cloudbees.plugins.credentials.CredentialsParameterDefinition") => {
+                ParameterType::Credentials {
+                    credential_type: raw.credential_type,
+                    required: raw.required,
+                }
+            }
+            _ => ParameterType::Other { class: raw.class },
+        };
+        ParameterDefinition {
+            name: raw.name,
+            description: raw
+                .description
+                .filter(|description| !description.is_empty()),
+            parameter_type,
+            default_value: raw
+                .default_parameter_value
+                .and_then(RawParameterValue::into_value),
+        }
+    }
+}
+
+impl ParameterDefinition {
+    /// Check that `value` is accepted by this parameter
+    pub fn validate(&self, value: &str) -> Result<()> {
+        let message = match self.parameter_type {
+            ParameterType::Boolean if value != "true" && value != "false" => {
+                "expected 'true' or 'false'".to_string()
+            }
+            ParameterType::Choice { ref choices } if !choices.iter().any(|c| c == value) => {
+                format!("expected one of {}", choices.join(", "))
+            }
+            ParameterType::Run { .. } if !is_run_id(value) => {
+                "expected a build as 'job#number'".to_string()
+            }
+            ParameterType::File => "expected a file, sent as a `FileParameter`".to_string(),
+            ParameterType::Credentials { required: true, .. } if value.is_empty() => {
+                "a credentials id is required".to_string()
+            }
+            _ => return Ok(()),
+        };
+        Err(client::Error::InvalidParameterValue {
+            name: self.name.clone(),
+            message,
+        })
+    }
+}
+
+/// Is `value` a build of a job as `job#number`
+fn is_run_id(value: &str) -> bool {
+    match value.rsplit_once('#') {
+        Some((job_name, number)) => !job_name.is_empty() && number.parse::<u32>().is_ok(),
+        None => false,
+    }
+}
+
+/// Properties of a job, read to get its parameter definitions
+#[derive(Deserialize, Debug)]
+pub(crate) struct JobParameterDefinitions {
+    #[serde(default)]
+    property: Vec<CommonProperty>,
+}
+
+impl JobParameterDefinitions {
+    /// Query parameters to get only the parameter definitions of a job
+    pub(crate) fn query() -> InternalAdvancedQueryParams {
+        InternalAdvancedQueryParams::from(AdvancedQuery::Tree(
+            TreeBuilder::object("property")
+                .with_subfield("_class")
+                .with_subfield(
+                    TreeBuilder::object("parameterDefinitions")
+                        .with_subfield("_class")
+                        .with_subfield("name")
+                        .with_subfield("description")
+                        .with_subfield("choices")
+                        .with_subfield("projectName")
+                        .with_subfield("credentialType")
+                        .with_subfield("required")
+                        .with_subfield(
+                            TreeBuilder::object("defaultParameterValue")
+                                .with_subfield("value")
+                                .with_subfield("jobName")
+                                .with_subfield("number"),
+                        ),
+                )
+                .build(),
+        ))
+    }
+
+    pub(crate) fn into_definitions(self) -> Vec<ParameterDefinition> {
+        self.property
+            .iter()
+            .filter_map(|property| property.as_variant::<ParametersDefinitionProperty>().ok())
+            .flat_map(|property| property.parameter_definitions)
+            .collect()
+    }
+}
+
+/// Check the parameters and files of a build against the definitions of the job, and add the
+/// default value of parameters left out
+pub(crate) fn resolve_parameters(
+    definitions: &[ParameterDefinition],
+    mut parameters: Vec<(String, String)>,
+    files: &[&str],
+) -> Result<Vec<(String, String)>> {
+    let definition = |name: &str| {
+        definitions
+            .iter()
+            .find(|definition| definition.name == name)
+            .ok_or_else(|| client::Error::UnknownParameter {
+                name: name.to_string(),
+            })
+    };
+    for (index, (name, value)) in parameters.iter().enumerate() {
+        let definition = definition(name)?;
+        definition.validate(value)?;
+        // Jenkins only keeps the first value of the parameter types it knows
+        if !matches!(definition.parameter_type, ParameterType::Other { .. })
+            && parameters[..index].iter().any(|(other, _)| other == name)
+        {
+            return Err(client::Error::InvalidParameterValue {
+                name: name.to_string(),
+                message: "this parameter accepts a single value".to_string(),
+            });
+        }
+    }
+    for name in files {
+        if definition(name)?.parameter_type != ParameterType::File {
+            return Err(client::Error::InvalidParameterValue {
+                name: name.to_string(),
+                message: "only file parameters accept a file".to_string(),
+            });
+        }
+    }
+    for definition in definitions {
+        if parameters.iter().any(|(name, _)| name == &definition.name)
+            || files.contains(&definition.name.as_str())
+        {
+            continue;
+        }
+        match (&definition.default_value, &definition.parameter_type) {
+            (Some(default_value), _) => {
+                parameters.push((definition.name.clone(), default_value.clone()))
+            }
+            (None, ParameterType::Credentials { required: true, .. }) => {
+                return Err(client::Error::InvalidParameterValue {
+                    name: definition.name.clone(),
+                    message: "a credentials id is required".to_string(),
+                });
+            }
+            (None, _) => (),
+        }
+    }
+    Ok(parameters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions() -> Vec<ParameterDefinition> {
+        let property: JobParameterDefinitions = serde_json::from_str(
+            r#"{"property": [
+                {"_class": "jenkins.model.BuildDiscarderProperty"},
+                {"_class": "hudson.model.ParametersDefinitionProperty", "parameterDefinitions": [
+                    {"_class": "hudson.model.StringParameterDefinition", "name": "TARGET", "description": "",
+                     "defaultParameterValue": {"_class": "hudson.model.StringParameterValue", "value": "all"}},
+                    {"_class": "hudson.model.BooleanParameterDefinition", "name": "DEPLOY",
+                     "defaultParameterValue": {"_class": "hudson.model.BooleanParameterValue", "value": false}},
+                    {"_class": "hudson.model.ChoiceParameterDefinition", "name": "MODE", "description": "Build mode",
+                     "choices": ["debug", "release"],
+                     "defaultParameterValue": {"_class": "hudson.model.StringParameterValue", "value": "debug"}},
+                    {"_class": "hudson.model.PasswordParameterDefinition", "name": "SECRET",
+                     "defaultParameterValue": {"_class": "hudson.model.PasswordParameterValue"}},
+                    {"_class": "hudson.model.RunParameterDefinition", "name": "UPSTREAM", "projectName": "team/upstream",
+                     "defaultParameterValue": {"_class": "hudson.model.RunParameterValue", "jobName": "team/upstream", "number": "12"}},
+                    {"_class": "hudson.model.FileParameterDefinition", "name": "archive.zip", "defaultParameterValue": null},
+                    {"_class": "com.cloudbees.plugins.credentials.CredentialsParameterDefinition", "name": "CREDS",
+                     "credentialType": "com.cloudbees.plugins.credentials.common.StandardCredentials", "required": true,
+                     "defaultParameterValue": null}
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+        property.into_definitions()
+    }
+
+    #[test]
+    fn can_read_parameter_definitions() {
+        let definitions = definitions();
+
+        assert_eq!(definitions.len(), 7);
+        assert_eq!(
+            definitions[0],
+            ParameterDefinition {
+                name: "TARGET".to_string(),
+                description: None,
+                parameter_type: ParameterType::String,
+                default_value: Some("all".to_string()),
+            }
+        );
+        assert_eq!(definitions[1].default_value.as_deref(), Some("false"));
+        assert_eq!(
+            definitions[2].parameter_type,
+            ParameterType::Choice {
+                choices: vec!["debug".to_string(), "release".to_string()]
+            }
+        );
+        assert_eq!(definitions[3].parameter_type, ParameterType::Password);
+        assert_eq!(definitions[3].default_value, None);
+        assert_eq!(
+            definitions[4].default_value.as_deref(),
+            Some("team/upstream#12")
+        );
+        assert_eq!(definitions[5].parameter_type, ParameterType::File);
+        assert_eq!(
+            definitions[6].parameter_type,
+            ParameterType::Credentials {
+                credential_type: Some(
+                    "com.cloudbees.plugins.credentials.common.StandardCredentials".to_string()
+                ),
+                required: true,
+            }
+        );
+    }
+
+    #[test]
+    fn can_fill_in_default_values() {
+        let parameters = resolve_parameters(
+            &definitions(),
+            vec![
+                ("MODE".to_string(), "release".to_string()),
+                ("CREDS".to_string(), "deploy-key".to_string()),
+            ],
+            &["archive.zip"],
+        )
+        .unwrap();
+
+        assert_eq!(
+            parameters,
+            vec![
+                ("MODE".to_string(), "release".to_string()),
+                ("CREDS".to_string(), "deploy-key".to_string()),
+                ("TARGET".to_string(), "all".to_string()),
+                ("DEPLOY".to_string(), "false".to_string()),
+                ("UPSTREAM".to_string(), "team/upstream#12".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_reject_invalid_values() {
+        let with = |name: &str, value: &str| {
+            resolve_parameters(
+                &definitions(),
+                vec![
+                    ("CREDS".to_string(), "deploy-key".to_string()),
+                    (name.to_string(), value.to_string()),
+                ],
+                &[],
+            )
+        };
+
+        for (name, value) in &[
+            ("MODE", "profile"),
+            ("DEPLOY", "yes"),
+            ("UPSTREAM", "team/upstream"),
+            ("archive.zip", "content"),
+            ("CREDS", ""),
+        ] {
+            match with(name, value) {
+                Err(client::Error::InvalidParameterValue { name: invalid, .. }) => {
+                    assert_eq!(&invalid, name)
+                }
+                other => panic!("expected an invalid value for {}, got {:?}", name, other),
+            }
+        }
+        match resolve_parameters(
+            &definitions(),
+            vec![
+                ("CREDS".to_string(), "deploy-key".to_string()),
+                ("TARGET".to_string(), "linux".to_string()),
+                ("TARGET".to_string(), "windows".to_string()),
+            ],
+            &[],
+        ) {
+            Err(client::Error::InvalidParameterValue { name, .. }) => assert_eq!(name, "TARGET"),
+            other => panic!("expected an invalid repeated value, got {:?}", other),
+        }
+        match with("UNKNOWN", "value") {
+            Err(client::Error::UnknownParameter { name }) => assert_eq!(name, "UNKNOWN"),
+            other => panic!("expected an unknown parameter, got {:?}", other),
+        }
+        match resolve_parameters(&definitions(), vec![], &[]) {
+            Err(client::Error::InvalidParameterValue { name, .. }) => assert_eq!(name, "CREDS"),
+            other => panic!("expected a missing credentials, got {:?}", other),
+        }
+        assert!(with("SECRET", "anything").is_ok());
+        match resolve_parameters(
+            &definitions(),
+            vec![("CREDS".to_string(), "deploy-key".to_string())],
+            &["TARGET"],
+        ) {
+            Err(client::Error::InvalidParameterValue { name, .. }) => assert_eq!(name, "TARGET"),
+            other => panic!(
+                "expected a file for a string parameter to fail, got {:?}",
+                other
+            ),
+        }
+    }
+}
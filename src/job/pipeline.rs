@@ -4,9 +4,12 @@ use crate::helpers::Class;
 
 use super::{BuildableJob, Job};
 use crate::action::CommonAction;
-use crate::build::{ShortBuild, WorkflowRun};
+use crate::build::{PipelineRun, ShortBuild, WorkflowRun};
+use crate::client::{self, Result};
+use crate::client_internals::{JsonResponse, Path, NO_PARAMS};
 use crate::property::CommonProperty;
 use crate::queue::ShortQueueItem;
+use crate::Jenkins;
 
 use super::{BallColor, HealthReport};
 
@@ -24,3 +27,25 @@ job_buildable_with_common_fields_and_impl!(
 register_class!("org.jenkinsci.plugins.workflow.job.WorkflowJob" => WorkflowJob);
 
 impl BuildableJob for WorkflowJob {}
+
+impl WorkflowJob {
+    /// Get the last runs of this job with their stages, as shown by the pipeline stage view
+    ///
+    /// The pipeline stage view API only returns the last 10 runs by default.
+    pub fn get_runs(&self, jenkins_client: &Jenkins) -> Result<Vec<PipelineRun>> {
+        let path = jenkins_client
+            .url_to_path(&self.url)?
+            .map_leaf(|path| match path {
+                Path::Job {
+                    name,
+                    configuration: None,
+                } => Some(Path::WorkflowRuns { job_name: name }),
+                _ => None,
+            })
+            .ok_or_else(|| client::Error::InvalidUrl {
+                url: self.url.clone(),
+                expected: client::error::ExpectedType::Job,
+            })?;
+        jenkins_client.get_raw(&path, NO_PARAMS)?.json_body()
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::CommonJob;
+use crate::client::{self, AdvancedQuery, Result, TreeBuilder, TreeQueryParam};
+use crate::client_internals::{InternalAdvancedQueryParams, JsonResponse, Path};
+use crate::Jenkins;
+
+/// Number of levels of folders read with each request while walking jobs
+const LEVELS_PER_REQUEST: usize = 4;
+
+/// A job found while walking all jobs with
+/// [`Jenkins::walk_jobs`](../struct.Jenkins.html#method.walk_jobs)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WalkedJob {
+    /// Full name of the job, including the folders containing it, like `folder/job`
+    pub full_name: String,
+    /// _class of the job
+    pub class: Option<String>,
+    /// URL for the job
+    pub url: String,
+}
+
+impl WalkedJob {
+    /// Get the full details of the job
+    pub fn get_full_job(&self, jenkins_client: &Jenkins) -> Result<CommonJob> {
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::Job { .. } = path.leaf() {
+            return jenkins_client.get(&path)?.json_body();
+        }
+        Err(client::Error::InvalidUrl {
+            url: self.url.clone(),
+            expected: client::error::ExpectedType::Job,
+        })
+    }
+}
+
+/// Item as read with the tree query of a walk
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WalkItem {
+    #[serde(rename = "_class")]
+    class: Option<String>,
+    #[serde(default)]
+    full_name: String,
+    #[serde(default)]
+    url: String,
+    jobs: Option<Vec<WalkItem>>,
+}
+
+/// Iterator over all jobs of Jenkins, in all folders, multibranch projects and organization
+/// folders
+///
+/// Folders are read a few levels at a time with a `tree` query, and deeper folders are read
+/// when the iterator reaches them.
+#[derive(Debug)]
+pub struct JobWalk<'a> {
+    jenkins_client: &'a Jenkins,
+    /// URLs of folders left to read, `None` being the home of Jenkins
+    pending: Vec<Option<String>>,
+    found: VecDeque<WalkedJob>,
+}
+
+impl<'a> JobWalk<'a> {
+    fn read(&mut self, url: Option<String>) -> Result<()> {
+        let path = match url {
+            Some(ref url) => folder_path(self.jenkins_client, url)?,
+            None => Path::Home,
+        };
+        let item: WalkItem = self
+            .jenkins_client
+            .get_with_params(
+                &path,
+                InternalAdvancedQueryParams::from(AdvancedQuery::Tree(walk_tree(
+                    LEVELS_PER_REQUEST,
+                ))),
+            )?
+            .json_body()?;
+        self.collect(item.jobs.unwrap_or_default(), LEVELS_PER_REQUEST);
+        Ok(())
+    }
+
+    fn collect(&mut self, items: Vec<WalkItem>, levels: usize) {
+        for item in items {
+            self.found.push_back(WalkedJob {
+                full_name: item.full_name,
+                class: item.class,
+                url: item.url.clone(),
+            });
+            match item.jobs {
+                Some(jobs) if levels > 1 => self.collect(jobs, levels - 1),
+                Some(ref jobs) if !jobs.is_empty() => self.pending.push(Some(item.url)),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Path to a folder found while walking jobs
+fn folder_path<'a>(jenkins_client: &Jenkins, url: &'a str) -> Result<Path<'a>> {
+    let path = jenkins_client.url_to_path(url)?;
+    if let Path::Job { .. } = path.leaf() {
+        Ok(path)
+    } else {
+        Err(client::Error::InvalidUrl {
+            url: url.to_string(),
+            expected: client::error::ExpectedType::Job,
+        })
+    }
+}
+
+/// Tree query reading `levels` levels of jobs, and if the jobs of the last level contain jobs
+fn walk_tree(levels: usize) -> TreeQueryParam {
+    let jobs = TreeBuilder::object("jobs")
+        .with_subfield("_class")
+        .with_subfield("fullName")
+        .with_subfield("url");
+    if levels > 1 {
+        jobs.with_subfield(walk_tree(levels - 1))
+    } else {
+        jobs.with_subfield(TreeBuilder::object("jobs").with_subfield("name"))
+    }
+    .build()
+}
+
+impl<'a> Iterator for JobWalk<'a> {
+    type Item = Result<WalkedJob>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(job) = self.found.pop_front() {
+                return Some(Ok(job));
+            }
+            let url = self.pending.pop()?;
+            if let Err(error) = self.read(url) {
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+impl Jenkins {
+    /// Walk all jobs of Jenkins, in all folders, multibranch projects and organization folders
+    ///
+    /// Containers are also returned, before the jobs they contain. Jobs are read a few levels
+    /// of folders at a time, so walking all jobs only takes a small number of requests.
+    pub fn walk_jobs(&self) -> JobWalk<'_> {
+        JobWalk {
+            jenkins_client: self,
+            pending: vec![None],
+            found: VecDeque::new(),
+        }
+    }
+}
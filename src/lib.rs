@@ -10,7 +10,6 @@
     unused_qualifications,
     missing_docs,
     unused_extern_crates,
-    unused_qualifications,
     unused_results
 )]
 #![allow(clippy::type_repetition_in_bounds)]
@@ -43,7 +42,9 @@
 //!
 
 mod client_internals;
-pub use crate::client_internals::{Jenkins, JenkinsBuilder};
+#[cfg(feature = "async")]
+pub use crate::client_internals::AsyncJenkins;
+pub use crate::client_internals::{Error, Jenkins, JenkinsBuilder};
 pub mod client;
 
 #[macro_use]
@@ -52,6 +53,7 @@ pub mod helpers;
 pub mod action;
 pub mod build;
 pub mod changeset;
+pub mod config;
 pub mod home;
 pub mod job;
 pub mod nodes;
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::client_internals::{Name, Path, Result};
+use crate::client_internals::{JsonResponse, Name, Path, Result};
 use crate::Jenkins;
 
 pub mod computer;
@@ -26,7 +26,7 @@ pub struct ComputerSet {
 impl Jenkins {
     /// Get a `ComputerSet`
     pub fn get_nodes(&self) -> Result<ComputerSet> {
-        Ok(self.get(&Path::Computers)?.json()?)
+        self.get(&Path::Computers)?.json_body()
     }
 
     /// Get a `Computer`
@@ -34,19 +34,17 @@ impl Jenkins {
     where
         C: Into<computer::ComputerName<'a>>,
     {
-        Ok(self
-            .get(&Path::Computer {
-                name: Name::Name(&computer_name.into().0),
-            })?
-            .json()?)
+        self.get(&Path::Computer {
+            name: Name::Name(computer_name.into().0),
+        })?
+        .json_body()
     }
 
     /// Get the master `Computer`
     pub fn get_master_node(&self) -> Result<computer::MasterComputer> {
-        Ok(self
-            .get(&Path::Computer {
-                name: Name::Name("(master)"),
-            })?
-            .json()?)
+        self.get(&Path::Computer {
+            name: Name::Name("(master)"),
+        })?
+        .json_body()
     }
 }
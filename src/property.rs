@@ -46,3 +46,31 @@ impl Property for RateLimitBranchProperty {}
 pub struct BuildDiscarderProperty {}
 register_class!("jenkins.model.BuildDiscarderProperty" => BuildDiscarderProperty);
 impl Property for BuildDiscarderProperty {}
+
+/// Job is a branch of a multibranch project
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchJobProperty {
+    /// The branch built by the job
+    pub branch: Branch,
+}
+register_class!("org.jenkinsci.plugins.workflow.multibranch.BranchJobProperty" => BranchJobProperty);
+impl Property for BranchJobProperty {}
+
+/// A branch of a multibranch project
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Branch {
+    /// Name of the branch
+    pub name: Option<String>,
+}
+
+/// Job is parameterized
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParametersDefinitionProperty {
+    /// Definitions of the parameters of the job
+    pub parameter_definitions: Vec<crate::job::ParameterDefinition>,
+}
+register_class!("hudson.model.ParametersDefinitionProperty" => ParametersDefinitionProperty);
+impl Property for ParametersDefinitionProperty {}
@@ -1,11 +1,13 @@
 //! Jenkins build queue
 
+use std::time::{Duration, Instant};
+
 use serde::{Deserialize, Serialize};
 
 use crate::action::CommonAction;
 use crate::build::ShortBuild;
 use crate::client::{self, Result};
-use crate::client_internals::Path;
+use crate::client_internals::{JsonResponse, Path};
 use crate::job::ShortJob;
 use crate::Jenkins;
 
@@ -26,19 +28,108 @@ pub struct ShortQueueItem {
 impl ShortQueueItem {
     /// Get the full details of a `QueueItem` matching the `ShortQueueItem`
     pub fn get_full_queue_item(&self, jenkins_client: &Jenkins) -> Result<QueueItem> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::QueueItem { .. } = path {
-            Ok(jenkins_client.get(&path)?.json()?)
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::QueueItem { .. } = path.leaf() {
+            Ok(jenkins_client.get(&path)?.json_body()?)
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url.clone(),
                 expected: client::error::ExpectedType::QueueItem,
+            })
+        }
+    }
+
+    /// Wait for the queue item to leave the queue and its build to start. When the queue item
+    /// has expired, its build is searched in the builds of the job at `job_path`
+    pub(crate) fn wait_started(
+        &self,
+        jenkins_client: &Jenkins,
+        job_path: &Path,
+        timeout: Duration,
+    ) -> Result<ShortBuild> {
+        let path = jenkins_client.url_to_path(&self.url)?;
+        let id = match path.leaf() {
+            Path::QueueItem { id } => *id,
+            _ => {
+                return Err(client::Error::InvalidUrl {
+                    url: self.url.clone(),
+                    expected: client::error::ExpectedType::QueueItem,
+                })
             }
-            .into())
+        };
+        let start = Instant::now();
+        loop {
+            match jenkins_client
+                .get_with_params(&path, [("tree", "cancelled,executable[number,url]")])
+            {
+                Ok(response) => {
+                    let state: QueueItemState = response.json_body()?;
+                    if state.cancelled == Some(true) {
+                        return Err(client::Error::QueueItemCancelled {
+                            url: self.url.clone(),
+                        });
+                    }
+                    if let Some(build) = state.executable {
+                        return Ok(build);
+                    }
+                }
+                Err(client::Error::NotFound { .. }) => {
+                    if let Some(build) = build_with_queue_id(jenkins_client, job_path, id)? {
+                        return Ok(build);
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Err(client::Error::Timeout {
+                    url: self.url.clone(),
+                    timeout,
+                });
+            }
+            std::thread::sleep(QUEUE_POLL_INTERVAL.min(timeout - elapsed));
         }
     }
 }
 
+/// Interval between two requests while waiting for a queue item to leave the queue
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// State of a queue item while waiting for its build to start
+#[derive(Debug, Deserialize)]
+struct QueueItemState {
+    cancelled: Option<bool>,
+    executable: Option<ShortBuild>,
+}
+
+/// Find the build of the job at `job_path` started from the queue item `id`
+fn build_with_queue_id(
+    jenkins_client: &Jenkins,
+    job_path: &Path,
+    id: i32,
+) -> Result<Option<ShortBuild>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct QueuedBuild {
+        queue_id: i32,
+        #[serde(flatten)]
+        build: ShortBuild,
+    }
+    #[derive(Deserialize)]
+    struct Builds {
+        builds: Vec<QueuedBuild>,
+    }
+
+    let builds: Builds = jenkins_client
+        .get_with_params(job_path, [("tree", "builds[number,url,queueId]")])?
+        .json_body()?;
+    Ok(builds
+        .builds
+        .into_iter()
+        .find(|build| build.queue_id == id)
+        .map(|build| build.build))
+}
+
 /// A queued item in Jenkins, with information about the `Job` and why / since when it's waiting
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -73,15 +164,14 @@ pub struct QueueItem {
 impl QueueItem {
     /// Refresh a `QueueItem`, consuming the existing one and returning a new `QueueItem`
     pub fn refresh_item(self, jenkins_client: &Jenkins) -> Result<Self> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::QueueItem { .. } = path {
-            Ok(jenkins_client.get(&path)?.json()?)
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::QueueItem { .. } = path.leaf() {
+            Ok(jenkins_client.get(&path)?.json_body()?)
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url.clone(),
                 expected: client::error::ExpectedType::QueueItem,
-            }
-            .into())
+            })
         }
     }
 }
@@ -97,11 +187,11 @@ pub struct Queue {
 impl Jenkins {
     /// Get the Jenkins items queue
     pub fn get_queue(&self) -> Result<Queue> {
-        Ok(self.get(&Path::Queue)?.json()?)
+        self.get(&Path::Queue)?.json_body()
     }
 
     /// Get a queue item from it's ID
     pub fn get_queue_item(&self, id: i32) -> Result<QueueItem> {
-        Ok(self.get(&Path::QueueItem { id })?.json()?)
+        self.get(&Path::QueueItem { id })?.json_body()
     }
 }
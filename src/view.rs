@@ -5,7 +5,7 @@ use serde::{self, Deserialize, Serialize};
 use crate::helpers::Class;
 
 use crate::client::{self, Result};
-use crate::client_internals::{Name, Path};
+use crate::client_internals::{JsonResponse, Name, Path};
 use crate::job::{JobName, ShortJob};
 use crate::property::CommonProperty;
 use crate::Jenkins;
@@ -31,15 +31,14 @@ pub struct ShortView {
 impl ShortView {
     /// Get the full details of a `View` matching the `ShortView`
     pub fn get_full_view(&self, jenkins_client: &Jenkins) -> Result<CommonView> {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::View { .. } = path {
-            Ok(jenkins_client.get(&path)?.json()?)
+        let path = jenkins_client.url_to_path(&self.url)?;
+        if let Path::View { .. } = path.leaf() {
+            Ok(jenkins_client.get(&path)?.json_body()?)
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url.clone(),
                 expected: client::error::ExpectedType::View,
-            }
-            .into())
+            })
         }
     }
 }
@@ -135,19 +134,24 @@ impl ListView {
     where
         J: Into<JobName<'a>>,
     {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::View { name } = path {
-            let _ = jenkins_client.post(&Path::AddJobToView {
-                job_name: Name::Name(&job_name.into().0),
-                view_name: name,
-            })?;
+        let job_name = job_name.into().0;
+        let path = jenkins_client
+            .url_to_path(&self.url)?
+            .map_leaf(|path| match path {
+                Path::View { name } => Some(Path::AddJobToView {
+                    job_name: Name::Name(job_name),
+                    view_name: name,
+                }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post(&path)?;
             Ok(())
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url.clone(),
                 expected: client::error::ExpectedType::View,
-            }
-            .into())
+            })
         }
     }
 
@@ -156,19 +160,24 @@ impl ListView {
     where
         J: Into<JobName<'a>>,
     {
-        let path = jenkins_client.url_to_path(&self.url);
-        if let Path::View { name } = path {
-            let _ = jenkins_client.post(&Path::RemoveJobFromView {
-                job_name: Name::Name(&job_name.into().0),
-                view_name: name,
-            })?;
+        let job_name = job_name.into().0;
+        let path = jenkins_client
+            .url_to_path(&self.url)?
+            .map_leaf(|path| match path {
+                Path::View { name } => Some(Path::RemoveJobFromView {
+                    job_name: Name::Name(job_name),
+                    view_name: name,
+                }),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let _ = jenkins_client.post(&path)?;
             Ok(())
         } else {
             Err(client::Error::InvalidUrl {
                 url: self.url.clone(),
                 expected: client::error::ExpectedType::View,
-            }
-            .into())
+            })
         }
     }
 }
@@ -179,11 +188,10 @@ impl Jenkins {
     where
         V: Into<ViewName<'a>>,
     {
-        Ok(self
-            .get(&Path::View {
-                name: Name::Name(&view_name.into().0),
-            })?
-            .json()?)
+        self.get(&Path::View {
+            name: Name::Name(view_name.into().0),
+        })?
+        .json_body()
     }
 
     /// Add the job `job_name` to the view `view_name`
@@ -193,8 +201,8 @@ impl Jenkins {
         J: Into<JobName<'a>>,
     {
         let _ = self.post(&Path::AddJobToView {
-            job_name: Name::Name(&job_name.into().0),
-            view_name: Name::Name(&view_name.into().0),
+            job_name: Name::Name(job_name.into().0),
+            view_name: Name::Name(view_name.into().0),
         })?;
         Ok(())
     }
@@ -206,8 +214,8 @@ impl Jenkins {
         J: Into<JobName<'a>>,
     {
         let _ = self.post(&Path::AddJobToView {
-            job_name: Name::Name(&job_name.into().0),
-            view_name: Name::Name(&view_name.into().0),
+            job_name: Name::Name(job_name.into().0),
+            view_name: Name::Name(view_name.into().0),
         })?;
         Ok(())
     }
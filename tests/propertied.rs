@@ -24,7 +24,7 @@ proptest! {
     fn doesnt_crash_user(ref s in "\\PC*") {
         setup();
         let jenkins = JenkinsBuilder::new(JENKINS_URL)
-            .with_user(&s, Some("password"))
+            .with_user(s, Some("password"))
             .build()
             .unwrap();
         jenkins.get_home().ok();
@@ -35,7 +35,7 @@ proptest! {
     #[test]
     fn doesnt_crash_url(ref s in "\\PC*") {
         setup();
-        if let Ok(jenkins) = JenkinsBuilder::new(&s)
+        if let Ok(jenkins) = JenkinsBuilder::new(s)
             .with_user("user", Some("password"))
             .build()
         {